@@ -0,0 +1,33 @@
+/*
+* Copyright (C) 2019-2021 TON Labs. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ton_vm::stack::integer::{behavior::Signaling, math::Round, IntegerData};
+
+fn bench_mod_mersenne(c: &mut Criterion) {
+    let value = IntegerData::from_str_radix(&"f".repeat(64), 16).unwrap();
+    let mut group = c.benchmark_group("mod_mersenne");
+    for p_bits in [13usize, 61, 127] {
+        let modulus = IntegerData::ones(p_bits).unwrap();
+        group.bench_with_input(BenchmarkId::new("fold", p_bits), &p_bits, |b, p_bits| {
+            b.iter(|| black_box(&value).mod_mersenne(*p_bits).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("div", p_bits), &modulus, |b, modulus| {
+            b.iter(|| black_box(&value).div::<Signaling>(modulus, Round::FloorToNegativeInfinity).unwrap().1)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mod_mersenne);
+criterion_main!(benches);
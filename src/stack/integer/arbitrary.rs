@@ -0,0 +1,99 @@
+/*
+* Copyright (C) 2019-2021 TON Labs. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::{Int, IntegerData};
+use num_traits::One;
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection::vec,
+    prop_oneof,
+    strategy::{BoxedStrategy, Just, Strategy},
+};
+
+/// Relative weights of generated kinds of values. Weights must not be all zero.
+#[derive(Clone, Copy, Debug)]
+pub struct ArbitraryWeights {
+    /// Weight of NaN.
+    pub nan: u32,
+    /// Weight of values spread across the whole 257-bit signed range.
+    pub value: u32,
+    /// Weight of boundary values: min, max, 0 and -1.
+    pub extreme: u32,
+}
+
+impl Default for ArbitraryWeights {
+    fn default() -> Self {
+        ArbitraryWeights { nan: 1, value: 14, extreme: 1 }
+    }
+}
+
+impl Arbitrary for IntegerData {
+    type Parameters = ArbitraryWeights;
+    type Strategy = BoxedStrategy<IntegerData>;
+
+    fn arbitrary_with(weights: Self::Parameters) -> Self::Strategy {
+        // random magnitude shifted right by random amount to cover all bit lengths
+        let value = (any::<bool>(), vec(any::<u8>(), 32), 0..=256usize)
+            .prop_map(|(negative, bytes, shift)| {
+                let sign = match negative {
+                    true => num::bigint::Sign::Minus,
+                    false => num::bigint::Sign::Plus,
+                };
+                let magnitude = num::BigUint::from_bytes_be(&bytes) >> shift;
+                IntegerData::from(Int::from_biguint(sign, magnitude)).expect("Should always fit")
+            });
+        let min = IntegerData::from(-(Int::one() << 256)).expect("Should always fit");
+        let max = IntegerData::from((Int::one() << 256) - 1).expect("Should always fit");
+        let extreme = prop_oneof![
+            Just(min),
+            Just(max),
+            Just(IntegerData::zero()),
+            Just(IntegerData::minus_one()),
+        ];
+        prop_oneof![
+            weights.nan => Just(IntegerData::nan()),
+            weights.value => value,
+            weights.extreme => extreme,
+        ].boxed()
+    }
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod tests {
+    use super::*;
+    use proptest::{strategy::ValueTree, test_runner::TestRunner};
+
+    #[test]
+    fn test_arbitrary_covers_nan_and_extremes() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = IntegerData::arbitrary();
+        let min = IntegerData::from(-(Int::one() << 256)).unwrap();
+        let max = IntegerData::from((Int::one() << 256) - 1).unwrap();
+        let (mut nan, mut lo, mut hi, mut zero, mut minus_one) = (0, 0, 0, 0, 0);
+        for _ in 0..10000 {
+            let value = strategy.new_tree(&mut runner).unwrap().current();
+            if value.is_nan() {
+                nan += 1;
+            } else if value == min {
+                lo += 1;
+            } else if value == max {
+                hi += 1;
+            } else if value.is_zero() {
+                zero += 1;
+            } else if value == IntegerData::minus_one() {
+                minus_one += 1;
+            }
+        }
+        assert!(nan > 0 && lo > 0 && hi > 0 && zero > 0 && minus_one > 0);
+    }
+}
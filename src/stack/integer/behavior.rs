@@ -12,6 +12,7 @@
 */
 
 use crate::{error::TvmError, types::{Exception, Status}};
+use std::sync::atomic::{AtomicBool, Ordering};
 use ton_types::{error, types::ExceptionCode};
 
 pub trait OperationBehavior {
@@ -24,6 +25,36 @@ pub trait OperationBehavior {
 
 pub struct Signaling {}
 pub struct Quiet {}
+/// Behavior determined at runtime by the global NaN policy, see set_nan_policy().
+pub struct Dynamic {}
+
+/// Behavior of operation chosen at runtime.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BehaviorKind {
+    Quiet,
+    Signaling,
+}
+
+/// Global policy of handling NaN and overflow used by Dynamic behavior.
+pub type NanPolicy = BehaviorKind;
+
+static QUIET_NAN_POLICY: AtomicBool = AtomicBool::new(false);
+
+/// Sets global NaN policy for Dynamic behavior. Default is Signaling.
+/// The policy is shared by all threads and is read with relaxed ordering:
+/// operations running concurrently with the change may observe either policy,
+/// so it should be set up before starting VM.
+pub fn set_nan_policy(policy: NanPolicy) {
+    QUIET_NAN_POLICY.store(policy == NanPolicy::Quiet, Ordering::Relaxed)
+}
+
+/// Returns current global NaN policy.
+pub fn nan_policy() -> NanPolicy {
+    match QUIET_NAN_POLICY.load(Ordering::Relaxed) {
+        true => NanPolicy::Quiet,
+        false => NanPolicy::Signaling,
+    }
+}
 
 #[macro_export]
 macro_rules! on_integer_overflow {
@@ -81,3 +112,52 @@ impl OperationBehavior for Quiet {
         Ok(())
     }
 }
+
+impl OperationBehavior for Dynamic {
+    fn quiet() -> bool {
+        nan_policy() == NanPolicy::Quiet
+    }
+    fn name_prefix() -> Option<&'static str> {
+        match nan_policy() {
+            NanPolicy::Quiet => Quiet::name_prefix(),
+            NanPolicy::Signaling => Signaling::name_prefix(),
+        }
+    }
+    fn on_integer_overflow(file: &'static str, line: u32) -> Status {
+        match nan_policy() {
+            NanPolicy::Quiet => Quiet::on_integer_overflow(file, line),
+            NanPolicy::Signaling => Signaling::on_integer_overflow(file, line),
+        }
+    }
+    fn on_nan_parameter(file: &'static str, line: u32) -> Status {
+        match nan_policy() {
+            NanPolicy::Quiet => Quiet::on_nan_parameter(file, line),
+            NanPolicy::Signaling => Signaling::on_nan_parameter(file, line),
+        }
+    }
+    fn on_range_check_error(file: &'static str, line: u32) -> Status {
+        match nan_policy() {
+            NanPolicy::Quiet => Quiet::on_range_check_error(file, line),
+            NanPolicy::Signaling => Signaling::on_range_check_error(file, line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::integer::{Int, IntegerData};
+    use num_traits::One;
+
+    #[test]
+    fn test_nan_policy() {
+        let max = IntegerData::from((Int::one() << 256) - 1).unwrap();
+        assert_eq!(nan_policy(), NanPolicy::Signaling);
+        set_nan_policy(NanPolicy::Quiet);
+        assert_eq!(nan_policy(), NanPolicy::Quiet);
+        let quiet = max.add::<Dynamic>(&IntegerData::one());
+        set_nan_policy(NanPolicy::Signaling);
+        assert!(quiet.unwrap().is_nan());
+        assert!(max.add::<Dynamic>(&IntegerData::one()).is_err());
+    }
+}
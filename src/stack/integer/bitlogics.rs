@@ -11,11 +11,19 @@
 * limitations under the License.
 */
 
-use crate::stack::integer::{
-    IntegerData, behavior::OperationBehavior,
-    utils::{binary_op, construct_single_nan, process_single_result, unary_op}
+use crate::{
+    error::TvmError,
+    stack::integer::{
+        Int, IntegerData, IntegerValue, behavior::{OperationBehavior, Quiet},
+        utils::{
+            binary_op, bitsize, construct_single_nan, process_single_result, process_value, unary_op
+        }
+    },
+    types::Exception,
 };
-use ton_types::Result;
+use num::ToPrimitive;
+use num_traits::{One, Signed, Zero};
+use ton_types::{error, ExceptionCode, Result};
 
 impl IntegerData {
     pub fn and<T>(&self, other: &IntegerData) -> Result<IntegerData>
@@ -92,5 +100,576 @@ impl IntegerData {
             process_single_result::<T, _>
         )
     }
+
+    /// Shifts value left by a positive amount or arithmetically right by a negative one.
+    pub fn shift<T>(&self, amount: &IntegerData) -> Result<IntegerData>
+    where
+        T: OperationBehavior
+    {
+        let amount = extract_value!(T, amount, construct_single_nan);
+        match amount.to_i32() {
+            Some(amount) if (0..=1023).contains(&amount) => self.shl::<T>(amount as usize),
+            Some(amount) if (-1023..0).contains(&amount) => self.shr::<T>(amount.unsigned_abs() as usize),
+            _ => {
+                on_range_check_error!(T)?;
+                Ok(construct_single_nan())
+            }
+        }
+    }
+
+    /// Clears low n bits of value rounding it down to a multiple of 2^n.
+    pub fn align_down(&self, n_bits: usize) -> Result<IntegerData> {
+        // any value is within 258 bits, so wider alignment can only result in 0 or overflow
+        let n_bits = n_bits.min(258);
+        process_value(self, |value| {
+            IntegerData::from((value >> n_bits) << n_bits)
+        })
+    }
+
+    /// Rounds value up to the next multiple of 2^n.
+    pub fn align_up(&self, n_bits: usize) -> Result<IntegerData> {
+        // any value is within 258 bits, so wider alignment can only result in 0 or overflow
+        let n_bits = n_bits.min(258);
+        process_value(self, |value| {
+            let mask = (Int::one() << n_bits) - 1;
+            IntegerData::from(((value + mask) >> n_bits) << n_bits)
+        })
+    }
+
+    /// Returns two's complement bit at a given index. Indices above the bit length
+    /// of value yield sign bit.
+    pub fn bit_at(&self, index: usize) -> Result<bool> {
+        process_value(self, |value| {
+            Ok(num::Integer::is_odd(&(value >> index)))
+        })
+    }
+
+    /// Determines a width which fits two's complement representations of both values.
+    /// NaN values are not taken into account.
+    pub fn common_width(a: &IntegerData, b: &IntegerData) -> usize {
+        let width = |x: &IntegerData| match x.value {
+            IntegerValue::NaN(_) => 0,
+            IntegerValue::Value(ref value) => bitsize(value),
+        };
+        std::cmp::max(width(a), width(b))
+    }
+
+    /// Extracts bits [start, start + len) of two's complement representation
+    /// and interprets them as a signed value.
+    pub fn extract_signed(&self, start: usize, len: usize) -> Result<IntegerData> {
+        if start.checked_add(len).is_none() {
+            return err!(ExceptionCode::RangeCheckError, "bits [{}, {} + {}) are out of range", start, start, len)
+        }
+        // bits above 257 copy the sign bit, so wider fields or offsets change nothing
+        let start = start.min(258);
+        let len = len.min(258);
+        process_value(self, |value| {
+            if len == 0 {
+                return Ok(IntegerData::zero())
+            }
+            let modulus = Int::one() << len;
+            let mut field = (value >> start) & (&modulus - 1);
+            if num::Integer::is_odd(&(&field >> (len - 1))) {
+                field -= modulus;
+            }
+            IntegerData::from(field)
+        })
+    }
+
+    /// Checks if all bits of a given mask are set in two's complement representation.
+    pub fn has_all_bits<T>(&self, mask: &IntegerData) -> Result<bool>
+    where
+        T: OperationBehavior
+    {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            mask,
+            |x, y| &(x & y) == y,
+            || false,
+            |result, _| Ok(result)
+        )
+    }
+
+    /// Checks if any bit of a given mask is set in two's complement representation.
+    pub fn has_any_bits<T>(&self, mask: &IntegerData) -> Result<bool>
+    where
+        T: OperationBehavior
+    {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            mask,
+            |x, y| !(x & y).is_zero(),
+            || false,
+            |result, _| Ok(result)
+        )
+    }
+
+    /// Folds XOR over a slice of values. Returns zero for empty slice and NaN if any value is NaN.
+    pub fn xor_all(items: &[IntegerData]) -> Result<IntegerData> {
+        items.iter().try_fold(IntegerData::zero(), |acc, item| acc.xor::<Quiet>(item))
+    }
+
+    /// Counts differing bits in two's complement representations of a given width.
+    /// Both values must fit into the width either as signed or as unsigned.
+    pub fn hamming_distance(&self, other: &IntegerData, width: usize) -> Result<IntegerData> {
+        for value in [self, other] {
+            if !value.fits_in(width)? && !value.ufits_in(width)? {
+                return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {}", value, width)
+            }
+        }
+        process_value(self, |x| process_value(other, |y| {
+            // bits above 258 are copies of the sign bit, so count them without building a mask
+            let clamped = width.min(258);
+            let diff = x ^ y;
+            let mut distance: u64 = (&diff & ((Int::one() << clamped) - 1)).magnitude()
+                .iter_u32_digits().map(|digit| digit.count_ones() as u64).sum();
+            if diff.is_negative() {
+                distance += (width - clamped) as u64;
+            }
+            Ok(IntegerData::from_u64(distance))
+        }))
+    }
+
+    /// Clears the most significant bit (width - 1) of two's complement representation
+    /// of a given width. Value must fit into the width either as signed or as unsigned.
+    pub fn clear_high_bit(&self, width: usize) -> Result<IntegerData> {
+        if width == 0 || (!self.fits_in(width)? && !self.ufits_in(width)?) {
+            return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {}", self, width)
+        }
+        // clearing bits above 257 keeps non-negative values and overflows negative ones either way
+        let width = width.min(258);
+        process_value(self, |value| {
+            IntegerData::from(value & ((Int::one() << (width - 1)) - 1))
+        })
+    }
+
+    /// Produces Morton code interleaving low bits of x (even positions) and y (odd positions).
+    pub fn interleave_bits(x: &IntegerData, y: &IntegerData, bits: usize) -> Result<IntegerData> {
+        for value in [x, y] {
+            if !value.ufits_in(bits)? {
+                return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {}", value, bits)
+            }
+        }
+        process_value(x, |x| process_value(y, |y| {
+            let mut result = Int::zero();
+            for i in (0..bits).rev() {
+                result <<= 2;
+                if num::Integer::is_odd(&(y >> i)) {
+                    result += 2;
+                }
+                if num::Integer::is_odd(&(x >> i)) {
+                    result += 1;
+                }
+            }
+            IntegerData::from(result)
+        }))
+    }
+
+    /// Splits Morton code back into (x, y) taking bits from even and odd positions.
+    /// Codes are at most 256 bits wide, so more than 128 bits per coordinate raise range check error.
+    pub fn deinterleave_bits(code: &IntegerData, bits: usize) -> Result<(IntegerData, IntegerData)> {
+        if bits > 128 {
+            return err!(ExceptionCode::RangeCheckError, "{} bits per coordinate exceed 256-bit code", bits)
+        }
+        if !code.ufits_in(2 * bits)? {
+            return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {}", code, 2 * bits)
+        }
+        process_value(code, |code| {
+            let mut x = Int::zero();
+            let mut y = Int::zero();
+            for i in (0..bits).rev() {
+                x <<= 1;
+                y <<= 1;
+                if num::Integer::is_odd(&(code >> (2 * i))) {
+                    x += 1;
+                }
+                if num::Integer::is_odd(&(code >> (2 * i + 1))) {
+                    y += 1;
+                }
+            }
+            Ok((IntegerData::from(x)?, IntegerData::from(y)?))
+        })
+    }
+
+    /// Returns n if non-negative value equals 2^n; otherwise None.
+    pub fn exact_log2(&self) -> Result<Option<usize>> {
+        self.check_neg()?;
+        process_value(self, |value| {
+            if value.is_zero() || !(value & (value - 1)).is_zero() {
+                return Ok(None)
+            }
+            Ok(Some(value.bits() as usize - 1))
+        })
+    }
+
+    /// Checks if low bits of two's complement representations of both values are equal.
+    pub fn eq_masked(&self, other: &IntegerData, bits: usize) -> Result<bool> {
+        process_value(self, |x| process_value(other, |y| {
+            // bits above 258 copy the sign bit, so comparing 258 bits is enough
+            let mask = (Int::one() << bits.min(258)) - 1;
+            Ok(((x ^ y) & mask).is_zero())
+        }))
+    }
+
+    /// Clears bits of value which are set in other (self & !other).
+    /// Returns NaN if any of values is NaN.
+    pub fn and_not(&self, other: &IntegerData) -> Result<IntegerData> {
+        binary_op::<Quiet, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| x & !y,
+            construct_single_nan,
+            process_single_result::<Quiet, _>
+        )
+    }
+
+    /// Computes remainder of GF(2) polynomial division (carry-less division) of value by poly.
+    /// Value must fit into width as unsigned, poly must be positive.
+    pub fn poly_mod2(&self, poly: &IntegerData, width: usize) -> Result<IntegerData> {
+        if !self.ufits_in(width)? {
+            return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {}", self, width)
+        }
+        if poly.is_neg() || poly.is_zero() {
+            return err!(ExceptionCode::RangeCheckError, "polynomial {} must be positive", poly)
+        }
+        process_value(self, |value| process_value(poly, |poly| {
+            let degree = poly.bits();
+            let mut remainder = value.clone();
+            while remainder.bits() >= degree {
+                remainder ^= poly << (remainder.bits() - degree) as usize;
+            }
+            IntegerData::from(remainder)
+        }))
+    }
+
+    /// Computes carry-less (GF(2)) product of magnitudes of both values.
+    pub fn clmul(&self, other: &IntegerData) -> Result<IntegerData> {
+        process_value(self, |x| process_value(other, |y| {
+            let x = x.magnitude();
+            let y = y.magnitude();
+            let mut result = num::BigUint::zero();
+            for i in 0..y.bits() {
+                if y.bit(i) {
+                    result ^= x << i as usize;
+                }
+            }
+            IntegerData::from(Int::from(result))
+        }))
+    }
+
+    /// Constructs unsigned mask with bits [start, start + len) set.
+    pub fn bit_range_mask(start: usize, len: usize) -> Result<IntegerData> {
+        if start.saturating_add(len) > 256 {
+            return err!(ExceptionCode::IntegerOverflow, "mask of {} bits from {} cannot fit in 256 bits", len, start)
+        }
+        IntegerData::from(((Int::one() << len) - 1) << start)
+    }
+
+    /// Replaces byte with a given little-endian index in unsigned representation of value.
+    pub fn replace_byte(&self, index: usize, byte: u8) -> Result<IntegerData> {
+        self.check_neg()?;
+        if index >= 32 {
+            // value fits into 256 bits, so higher bytes are always zero
+            if byte == 0 {
+                return Ok(self.clone())
+            }
+            return err!(ExceptionCode::IntegerOverflow, "byte {} cannot fit in 256 bits", index)
+        }
+        process_value(self, |value| {
+            let shift = index * 8;
+            let old = (value >> shift) & Int::from(0xffu32);
+            IntegerData::from(value - (old << shift) + (Int::from(byte) << shift))
+        })
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::integer::behavior::Signaling;
+    use crate::stack::integer::tests::exception_code;
+
+    #[test]
+    fn test_shift() {
+        let value = IntegerData::from_i32(3);
+        assert_eq!(value.shift::<Signaling>(&IntegerData::from_i32(5)).unwrap(), IntegerData::from_i32(96));
+        assert_eq!(value.shift::<Signaling>(&IntegerData::zero()).unwrap(), value);
+        let value = IntegerData::from_i32(96);
+        assert_eq!(value.shift::<Signaling>(&IntegerData::from_i32(-5)).unwrap(), IntegerData::from_i32(3));
+        let value = IntegerData::from_i32(-96);
+        assert_eq!(value.shift::<Signaling>(&IntegerData::from_i32(-5)).unwrap(), IntegerData::from_i32(-3));
+    }
+
+    #[test]
+    fn test_shift_out_of_range() {
+        let value = IntegerData::one();
+        let amount = IntegerData::from_i32(1024);
+        assert_eq!(exception_code(value.shift::<Signaling>(&amount)), Some(ExceptionCode::RangeCheckError));
+        assert!(value.shift::<Quiet>(&amount).unwrap().is_nan());
+        assert_eq!(exception_code(value.shift::<Signaling>(&IntegerData::from_i32(300))), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_align() {
+        let value = IntegerData::from_i32(13);
+        assert_eq!(value.align_down(2).unwrap(), IntegerData::from_i32(12));
+        assert_eq!(value.align_up(2).unwrap(), IntegerData::from_i32(16));
+        let value = IntegerData::from_i32(12);
+        assert_eq!(value.align_down(2).unwrap(), value);
+        assert_eq!(value.align_up(2).unwrap(), value);
+        let value = IntegerData::from_i32(-13);
+        assert_eq!(value.align_down(2).unwrap(), IntegerData::from_i32(-16));
+        assert_eq!(value.align_up(2).unwrap(), IntegerData::from_i32(-12));
+    }
+
+    #[test]
+    fn test_align_errors() {
+        assert_eq!(exception_code(IntegerData::nan().align_down(2)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::one().align_up(256)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::one().align_up(usize::MAX)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::minus_one().align_down(usize::MAX)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_align_huge() {
+        assert_eq!(IntegerData::from_i32(13).align_down(usize::MAX).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::from_i32(-13).align_up(usize::MAX).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::zero().align_up(usize::MAX).unwrap(), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_bit_at() {
+        assert!(IntegerData::minus_one().bit_at(1000).unwrap());
+        assert!(!IntegerData::from_i32(5).bit_at(1000).unwrap());
+        assert!(IntegerData::from_i32(5).bit_at(0).unwrap());
+        assert!(!IntegerData::from_i32(5).bit_at(1).unwrap());
+        // -6 is ...11010
+        assert!(!IntegerData::from_i32(-6).bit_at(0).unwrap());
+        assert!(IntegerData::from_i32(-6).bit_at(1).unwrap());
+        assert!(!IntegerData::from_i32(-6).bit_at(2).unwrap());
+        assert_eq!(exception_code(IntegerData::nan().bit_at(0)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_common_width() {
+        assert_eq!(IntegerData::common_width(&IntegerData::minus_one(), &IntegerData::from_i32(5)), 4);
+        assert_eq!(IntegerData::common_width(&IntegerData::zero(), &IntegerData::zero()), 1);
+        assert_eq!(IntegerData::common_width(&IntegerData::from_i32(-129), &IntegerData::from_i32(127)), 9);
+        assert_eq!(IntegerData::common_width(&IntegerData::nan(), &IntegerData::from_i32(5)), 4);
+    }
+
+    #[test]
+    fn test_extract_signed() {
+        let extract = |value: i32, start: usize, len: usize| IntegerData::from_i32(value).extract_signed(start, len).unwrap();
+        assert_eq!(extract(0b1111, 0, 4), IntegerData::minus_one());
+        assert_eq!(extract(0b0111, 0, 4), IntegerData::from_i32(7));
+        assert_eq!(extract(0xF0, 4, 4), IntegerData::minus_one());
+        assert_eq!(extract(0x50, 4, 4), IntegerData::from_i32(5));
+        assert_eq!(extract(-1, 100, 3), IntegerData::minus_one());
+        assert_eq!(extract(-1, 0, 0), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_has_bits() {
+        let flags = IntegerData::from_u32(0b1011);
+        assert!(flags.has_all_bits::<Signaling>(&IntegerData::from_u32(0b0011)).unwrap());
+        assert!(!flags.has_all_bits::<Signaling>(&IntegerData::from_u32(0b0111)).unwrap());
+        assert!(!flags.has_any_bits::<Signaling>(&IntegerData::from_u32(0b0100)).unwrap());
+        assert!(flags.has_any_bits::<Signaling>(&IntegerData::from_u32(0b1100)).unwrap());
+        assert!(IntegerData::minus_one().has_all_bits::<Signaling>(&flags).unwrap());
+    }
+
+    #[test]
+    fn test_has_bits_nan() {
+        let flags = IntegerData::from_u32(0b1011);
+        assert_eq!(exception_code(IntegerData::nan().has_all_bits::<Signaling>(&flags)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(flags.has_any_bits::<Signaling>(&IntegerData::nan())), Some(ExceptionCode::IntegerOverflow));
+        assert!(!IntegerData::nan().has_all_bits::<Quiet>(&flags).unwrap());
+        assert!(!flags.has_any_bits::<Quiet>(&IntegerData::nan()).unwrap());
+    }
+
+    #[test]
+    fn test_xor_all() {
+        let value = IntegerData::from_i32(-12345);
+        assert_eq!(IntegerData::xor_all(&[value.clone(), value.clone()]).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::xor_all(&[]).unwrap(), IntegerData::zero());
+        let items = [IntegerData::from_i32(1), IntegerData::from_i32(2), IntegerData::from_i32(4)];
+        assert_eq!(IntegerData::xor_all(&items).unwrap(), IntegerData::from_i32(7));
+        assert!(IntegerData::xor_all(&[value, IntegerData::nan()]).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let distance = |x: i32, y: i32, width: usize| {
+            IntegerData::from_i32(x).hamming_distance(&IntegerData::from_i32(y), width).unwrap()
+        };
+        assert_eq!(distance(0b1010, 0b0110, 4), IntegerData::from_u32(2));
+        assert_eq!(distance(-1, 0, 8), IntegerData::from_u32(8));
+        assert_eq!(distance(-1, 255, 8), IntegerData::zero());
+        assert_eq!(distance(-1, 0, 1000), IntegerData::from_u32(1000));
+        assert_eq!(distance(-1, -2, usize::MAX), IntegerData::one());
+        assert_eq!(distance(-1, 0, usize::MAX), IntegerData::from_u64(usize::MAX as u64));
+    }
+
+    #[test]
+    fn test_hamming_distance_errors() {
+        let result = IntegerData::from_i32(16).hamming_distance(&IntegerData::zero(), 4);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let result = IntegerData::nan().hamming_distance(&IntegerData::zero(), 4);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_clear_high_bit() {
+        let clear = |x: i32, width: usize| IntegerData::from_i32(x).clear_high_bit(width);
+        assert_eq!(clear(-1, 8).unwrap(), IntegerData::from_i32(127));
+        assert_eq!(clear(0x80, 8).unwrap(), IntegerData::zero());
+        assert_eq!(clear(5, 8).unwrap(), IntegerData::from_i32(5));
+        assert_eq!(exception_code(clear(256, 8)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(clear(0, 0)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_interleave_bits() {
+        let code = IntegerData::interleave_bits(&IntegerData::from_u32(5), &IntegerData::from_u32(3), 3).unwrap();
+        assert_eq!(code, IntegerData::from_u32(27));
+        for x in 0..8 {
+            for y in 0..8 {
+                let (x, y) = (IntegerData::from_u32(x), IntegerData::from_u32(y));
+                let code = IntegerData::interleave_bits(&x, &y, 3).unwrap();
+                assert_eq!(IntegerData::deinterleave_bits(&code, 3).unwrap(), (x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_interleave_bits_errors() {
+        let result = IntegerData::interleave_bits(&IntegerData::from_u32(8), &IntegerData::zero(), 3);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let result = IntegerData::interleave_bits(&IntegerData::zero(), &IntegerData::minus_one(), 3);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::deinterleave_bits(&IntegerData::from_u32(64), 3)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_exact_log2() {
+        assert_eq!(IntegerData::from_u32(256).exact_log2().unwrap(), Some(8));
+        assert_eq!(IntegerData::from_u32(255).exact_log2().unwrap(), None);
+        assert_eq!(IntegerData::one().exact_log2().unwrap(), Some(0));
+        assert_eq!(IntegerData::zero().exact_log2().unwrap(), None);
+        assert_eq!(IntegerData::from(Int::one() << 255).unwrap().exact_log2().unwrap(), Some(255));
+        assert_eq!(exception_code(IntegerData::from_i32(-4).exact_log2()), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_eq_masked() {
+        let eq = |x: i32, y: i32, bits: usize| IntegerData::from_i32(x).eq_masked(&IntegerData::from_i32(y), bits).unwrap();
+        assert!(eq(0x1FF, 0x0FF, 8));
+        assert!(!eq(0x1FF, 0x0FF, 9));
+        assert!(eq(-1, 255, 8));
+        assert!(!eq(-1, 255, 9));
+        assert!(eq(-1, -1, usize::MAX));
+        assert!(!eq(-1, 0, usize::MAX));
+        assert!(eq(5, 6, 0));
+        assert_eq!(exception_code(IntegerData::nan().eq_masked(&IntegerData::zero(), 8)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_and_not() {
+        let and_not = |x: i32, y: i32| IntegerData::from_i32(x).and_not(&IntegerData::from_i32(y)).unwrap();
+        assert_eq!(and_not(0b1111, 0b0101), IntegerData::from_i32(0b1010));
+        assert_eq!(and_not(-1, 0xFF), IntegerData::from_i32(-256));
+        assert_eq!(and_not(0xFF, -1), IntegerData::zero());
+        assert!(IntegerData::nan().and_not(&IntegerData::one()).unwrap().is_nan());
+        assert!(IntegerData::one().and_not(&IntegerData::nan()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_poly_mod2() {
+        // CRC-8 with polynomial x^8 + x^2 + x + 1 of "123456789" followed by 8 zero bits
+        let message = IntegerData::from_unsigned_bytes_be(b"123456789\0");
+        let crc = message.poly_mod2(&IntegerData::from_u32(0x107), 80).unwrap();
+        assert_eq!(crc, IntegerData::from_u32(0xF4));
+        let remainder = IntegerData::from_u32(0b1101).poly_mod2(&IntegerData::from_u32(0b11), 4).unwrap();
+        assert_eq!(remainder, IntegerData::one());
+        let remainder = IntegerData::from_u32(0b101).poly_mod2(&IntegerData::from_u32(0b1000), 4).unwrap();
+        assert_eq!(remainder, IntegerData::from_u32(0b101));
+    }
+
+    #[test]
+    fn test_poly_mod2_errors() {
+        let value = IntegerData::from_u32(0b1101);
+        assert_eq!(exception_code(value.poly_mod2(&IntegerData::zero(), 4)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(value.poly_mod2(&IntegerData::minus_one(), 4)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(value.poly_mod2(&IntegerData::from_u32(0b11), 3)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_clmul() {
+        let clmul = |x: i32, y: i32| IntegerData::from_i32(x).clmul(&IntegerData::from_i32(y)).unwrap();
+        assert_eq!(clmul(3, 3), IntegerData::from_i32(5));
+        assert_eq!(clmul(-3, 3), IntegerData::from_i32(5));
+        assert_eq!(clmul(0b1011, 0), IntegerData::zero());
+        let (a, b) = (IntegerData::from_u32(0b1011), IntegerData::from_u32(0b110));
+        let product = a.clmul(&b).unwrap();
+        assert_eq!(product.poly_mod2(&b, 8).unwrap(), IntegerData::zero());
+        let x = IntegerData::from(Int::one() << 200).unwrap();
+        let y = IntegerData::from(Int::one() << 100).unwrap();
+        assert_eq!(exception_code(x.clmul(&y)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_bit_range_mask() {
+        assert_eq!(IntegerData::bit_range_mask(4, 4).unwrap(), IntegerData::from_u32(0xF0));
+        assert_eq!(IntegerData::bit_range_mask(0, 0).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::bit_range_mask(256, 0).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::bit_range_mask(0, 256).unwrap(), IntegerData::from((Int::one() << 256) - 1).unwrap());
+        assert_eq!(exception_code(IntegerData::bit_range_mask(1, 256)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::bit_range_mask(usize::MAX, 2)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_replace_byte() {
+        let value = IntegerData::from_u32(0x1234);
+        assert_eq!(value.replace_byte(0, 0xAB).unwrap(), IntegerData::from_u32(0x12AB));
+        assert_eq!(value.replace_byte(1, 0xAB).unwrap(), IntegerData::from_u32(0xAB34));
+        assert_eq!(value.replace_byte(2, 0xAB).unwrap(), IntegerData::from_u32(0xAB1234));
+        assert_eq!(value.replace_byte(1, 0).unwrap(), IntegerData::from_u32(0x34));
+        assert_eq!(value.replace_byte(40, 0).unwrap(), value);
+        assert_eq!(IntegerData::zero().replace_byte(31, 0x80).unwrap(), IntegerData::from(Int::one() << 255).unwrap());
+    }
+
+    #[test]
+    fn test_replace_byte_errors() {
+        let value = IntegerData::from_u32(0x1234);
+        assert_eq!(exception_code(value.replace_byte(40, 1)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::minus_one().replace_byte(0, 1)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::nan().replace_byte(0, 1)), Some(ExceptionCode::RangeCheckError));
+    }
+
+        #[test]
+        fn test_clear_high_bit_huge_width() {
+            assert_eq!(IntegerData::from_u32(5).clear_high_bit(usize::MAX).unwrap(), IntegerData::from_u32(5));
+            assert_eq!(exception_code(IntegerData::minus_one().clear_high_bit(usize::MAX)), Some(ExceptionCode::IntegerOverflow));
+        }
+
+        #[test]
+        fn test_extract_signed_huge_bounds() {
+            let value = IntegerData::from_i32(-6);
+            assert_eq!(value.extract_signed(1, usize::MAX - 1).unwrap(), IntegerData::from_i32(-3));
+            assert_eq!(value.extract_signed(usize::MAX - 1, 1).unwrap(), IntegerData::minus_one());
+            assert_eq!(IntegerData::from_u32(6).extract_signed(usize::MAX - 1, 1).unwrap(), IntegerData::zero());
+            assert_eq!(exception_code(value.extract_signed(usize::MAX, 1)), Some(ExceptionCode::RangeCheckError));
+        }
+
+        #[test]
+        fn test_deinterleave_bits_wide() {
+            let (x, y) = IntegerData::interleave_bits(&IntegerData::from_u32(5), &IntegerData::from_u32(3), 128)
+                .and_then(|code| IntegerData::deinterleave_bits(&code, 128))
+                .unwrap();
+            assert_eq!((x, y), (IntegerData::from_u32(5), IntegerData::from_u32(3)));
+            let code = |bits: usize| exception_code(IntegerData::deinterleave_bits(&IntegerData::one(), bits));
+            assert_eq!(code(129), Some(ExceptionCode::RangeCheckError));
+            assert_eq!(code(usize::MAX), Some(ExceptionCode::RangeCheckError));
+        }
+}
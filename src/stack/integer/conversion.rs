@@ -19,7 +19,8 @@ use crate::{
     },
     types::Exception,
 };
-use num_traits::Num;
+use num::ToPrimitive;
+use num_traits::{Num, Signed, Zero};
 use std::ops::RangeInclusive;
 use ton_types::{error, types::ExceptionCode, Result};
 
@@ -85,6 +86,38 @@ impl IntegerData {
         }
     }
 
+    /// Constructs new IntegerData value equal to high * 2^128 + low.
+    #[inline]
+    pub fn from_u128_pair(high: u128, low: u128) -> Result<IntegerData> {
+        Self::from((Int::from(high) << 128) | Int::from(low))
+    }
+
+    /// Splits non-negative value fitting into 256 bits into high and low u128 halves.
+    pub fn to_u128_pair(&self) -> Option<(u128, u128)> {
+        match self.value {
+            IntegerValue::Value(ref value) if !value.is_negative() && value.bits() <= 256 => {
+                let low = (value & Int::from(u128::MAX)).to_u128()?;
+                let high = (value >> 128).to_u128()?;
+                Some((high, low))
+            }
+            _ => None,
+        }
+    }
+
+    /// Constructs new IntegerData from Unicode code point of a char.
+    #[inline]
+    pub fn from_char(c: char) -> IntegerData {
+        IntegerData::from_u32(c as u32)
+    }
+
+    /// Converts value into char if it is a valid Unicode scalar value; otherwise None.
+    pub fn to_char(&self) -> Option<char> {
+        match self.value {
+            IntegerValue::Value(ref value) => value.to_u32().and_then(char::from_u32),
+            IntegerValue::NaN(_) => None,
+        }
+    }
+
     /// Constructs new IntegerData value from the given one of another supported type.
     #[inline]
     pub fn from(value: impl Into<Int>) -> Result<IntegerData> {
@@ -98,6 +131,15 @@ impl IntegerData {
         }
     }
 
+    /// Constructs new IntegerData value from a given Int, returning it back on overflow.
+    #[inline]
+    pub fn from_or_return(value: Int) -> std::result::Result<IntegerData, Int> {
+        match check_overflow(&value) {
+            true => Ok(IntegerData { value: IntegerValue::Value(value) }),
+            false => Err(value),
+        }
+    }
+
     /// Constructs new IntegerData value from the little-endian slice of u32
     /// without overflow checking.
     #[inline]
@@ -120,6 +162,14 @@ impl IntegerData {
         })
     }
 
+    /// Constructs new IntegerData value from sign and magnitude with overflow checking.
+    pub fn from_biguint(sign: num::bigint::Sign, magnitude: num::BigUint) -> Result<IntegerData> {
+        if sign == num::bigint::Sign::NoSign && !magnitude.is_zero() {
+            return err!(ExceptionCode::RangeCheckError, "{} has no sign", magnitude);
+        }
+        Self::from(Int::from_biguint(sign, magnitude))
+    }
+
     /// Parses string literal with given radix and constructs new IntegerData.
     pub fn from_str_radix(literal: &str, radix: u32) -> Result<IntegerData> {
         match Int::from_str_radix(literal, radix) {
@@ -128,13 +178,52 @@ impl IntegerData {
         }
     }
 
+    /// Parses decimal ASCII digits with optional leading sign and constructs new IntegerData.
+    pub fn from_decimal_ascii(bytes: &[u8]) -> Result<IntegerData> {
+        let (sign, digits) = match bytes.split_first() {
+            Some((b'-', digits)) => (num::bigint::Sign::Minus, digits),
+            Some((b'+', digits)) => (num::bigint::Sign::Plus, digits),
+            _ => (num::bigint::Sign::Plus, bytes),
+        };
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return err!(ExceptionCode::TypeCheckError);
+        }
+        match num::BigUint::parse_bytes(digits, 10) {
+            Some(magnitude) => Self::from(Int::from_biguint(sign, magnitude)),
+            None => err!(ExceptionCode::TypeCheckError),
+        }
+    }
+
+    /// Parses hex string of two's complement representation in a given bits size
+    /// (most significant bit is sign) and constructs new IntegerData.
+    pub fn from_twos_complement_hex(s: &str, bits: usize) -> Result<IntegerData> {
+        if bits == 0 || s.len() != bits.div_ceil(4) {
+            return err!(ExceptionCode::RangeCheckError, "{} bits value must have {} hex digits", bits, bits.div_ceil(4));
+        }
+        if !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return err!(ExceptionCode::TypeCheckError);
+        }
+        let value = match Int::from_str_radix(s, 16) {
+            Ok(value) => value,
+            Err(_) => return err!(ExceptionCode::TypeCheckError),
+        };
+        if value.bits() as usize > bits {
+            return err!(ExceptionCode::RangeCheckError, "{} does not fit into {} bits", s, bits);
+        }
+        if value.bit(bits as u64 - 1) {
+            Self::from(value - (Int::from(1) << bits))
+        } else {
+            Self::from(value)
+        }
+    }
+
     /// Returns value converted into given type with range checking.
     pub fn into<T>(&self, range: RangeInclusive<T>) -> Result<T>
     where
         T: PartialOrd + std::fmt::Display + FromInt,
     {
         match self.value {
-            IntegerValue::NaN => err!(ExceptionCode::RangeCheckError, "not a number"),
+            IntegerValue::NaN(_) => err!(ExceptionCode::RangeCheckError, "not a number"),
             IntegerValue::Value(ref value) => {
                 T::from_int(value).and_then(|ret| {
                     if *range.start() > ret || *range.end() < ret {
@@ -146,11 +235,33 @@ impl IntegerData {
         }
     }
 
+    /// Splits unsigned big-endian representation of a given byte width into groups of bytes.
+    pub fn to_byte_groups(&self, group_bytes: usize, total_bytes: usize) -> Result<Vec<IntegerData>> {
+        if group_bytes == 0 || total_bytes == 0 || total_bytes % group_bytes != 0 {
+            return err!(
+                ExceptionCode::RangeCheckError,
+                "{} bytes cannot be split into groups of {}", total_bytes, group_bytes
+            );
+        }
+        // no value needs more than 33 bytes, so wider buffers are not worth allocating
+        let bits = match total_bytes.checked_mul(8) {
+            Some(bits) if total_bytes <= 33 => bits,
+            _ => return err!(ExceptionCode::RangeCheckError, "{} bytes are wider than any value", total_bytes),
+        };
+        if !self.ufits_in(bits)? {
+            return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {} bytes", self, total_bytes);
+        }
+        let bytes = self.take_value_of(|x| x.to_biguint())?.to_bytes_be();
+        let mut buffer = vec![0; total_bytes - bytes.len()];
+        buffer.extend_from_slice(&bytes);
+        Ok(buffer.chunks(group_bytes).map(IntegerData::from_unsigned_bytes_be).collect())
+    }
+
     /// Extracts internal value using conversion function. Returns IntegerOverflow exception on NaN.
     #[inline]
     pub fn take_value_of<T>(&self, convert: impl Fn(&Int) -> Option<T>) -> Result<T> {
         match self.value {
-            IntegerValue::NaN => err!(ExceptionCode::IntegerOverflow, "not a number"),
+            IntegerValue::NaN(_) => err!(ExceptionCode::IntegerOverflow, "not a number"),
             IntegerValue::Value(ref value) => {
                 if let Some(value) = convert(value) {
                     Ok(value)
@@ -290,3 +401,123 @@ auto_from_int! {
     usize: to_usize,
     isize: to_isize
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::integer::tests::exception_code;
+
+    #[test]
+    fn test_from_biguint() {
+        let magnitude = num::BigUint::from(1u32) << 256;
+        let value = IntegerData::from_biguint(num::bigint::Sign::Minus, magnitude.clone()).unwrap();
+        assert_eq!(value, IntegerData::from(-(Int::from(1) << 256)).unwrap());
+        assert_eq!(
+            exception_code(IntegerData::from_biguint(num::bigint::Sign::Plus, magnitude)),
+            Some(ExceptionCode::IntegerOverflow)
+        );
+    }
+
+    #[test]
+    fn test_from_biguint_without_sign() {
+        let value = IntegerData::from_biguint(num::bigint::Sign::NoSign, num::BigUint::zero()).unwrap();
+        assert!(value.is_zero());
+        assert_eq!(
+            exception_code(IntegerData::from_biguint(num::bigint::Sign::NoSign, num::BigUint::from(5u32))),
+            Some(ExceptionCode::RangeCheckError)
+        );
+    }
+
+    #[test]
+    fn test_from_or_return() {
+        let value = Int::from(1) << 257;
+        assert_eq!(IntegerData::from_or_return(value.clone()), Err(value));
+        let value = (Int::from(1) << 256) - 1;
+        assert_eq!(IntegerData::from_or_return(value.clone()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_from_decimal_ascii() {
+        assert_eq!(IntegerData::from_decimal_ascii(b"-123").unwrap(), IntegerData::from_i32(-123));
+        assert_eq!(IntegerData::from_decimal_ascii(b"+7").unwrap(), IntegerData::from_i32(7));
+        assert_eq!(IntegerData::from_decimal_ascii(b"0").unwrap(), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_from_decimal_ascii_invalid() {
+        for bytes in [&b"12a"[..], b"", b"-", b"1 2", b"--1"] {
+            assert_eq!(exception_code(IntegerData::from_decimal_ascii(bytes)), Some(ExceptionCode::TypeCheckError));
+        }
+    }
+
+    #[test]
+    fn test_to_byte_groups() {
+        let octets = IntegerData::from_u32(0xC0A8_0001).to_byte_groups(1, 4).unwrap();
+        let expected: Vec<IntegerData> = [192, 168, 0, 1].iter().map(|x| IntegerData::from_u32(*x)).collect();
+        assert_eq!(octets, expected);
+        let groups = IntegerData::from_u32(0x0102).to_byte_groups(2, 4).unwrap();
+        assert_eq!(groups, vec![IntegerData::zero(), IntegerData::from_u32(0x0102)]);
+    }
+
+    #[test]
+    fn test_to_byte_groups_errors() {
+        let value = IntegerData::from_u64(1 << 32);
+        assert_eq!(exception_code(value.to_byte_groups(1, 4)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::minus_one().to_byte_groups(1, 4)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::one().to_byte_groups(3, 4)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::one().to_byte_groups(0, 0)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_u128_pair() {
+        for (high, low) in [(0, 0), (1, u128::MAX), (u128::MAX, 0), (u128::MAX, u128::MAX)] {
+            let value = IntegerData::from_u128_pair(high, low).unwrap();
+            assert_eq!(value.to_u128_pair(), Some((high, low)));
+        }
+        assert_eq!(IntegerData::minus_one().to_u128_pair(), None);
+        assert_eq!(IntegerData::nan().to_u128_pair(), None);
+    }
+
+    #[test]
+    fn test_char_conversion() {
+        for c in ['A', '\0', 'я', '\u{10FFFF}'] {
+            assert_eq!(IntegerData::from_char(c).to_char(), Some(c));
+        }
+        assert_eq!(IntegerData::from_char('A'), IntegerData::from_u32(65));
+        assert_eq!(IntegerData::from_u32(0xD800).to_char(), None);
+        assert_eq!(IntegerData::from_u32(0x110000).to_char(), None);
+        assert_eq!(IntegerData::minus_one().to_char(), None);
+        assert_eq!(IntegerData::nan().to_char(), None);
+    }
+
+    #[test]
+    fn test_twos_complement_hex_round_trip() {
+        for x in -128..=127 {
+            let value = IntegerData::from_i32(x);
+            let hex = value.to_twos_complement_hex(8).unwrap();
+            assert_eq!(IntegerData::from_twos_complement_hex(&hex, 8).unwrap(), value, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn test_from_twos_complement_hex() {
+        let parse = |s: &str, bits: usize| IntegerData::from_twos_complement_hex(s, bits);
+        assert_eq!(parse("fff", 12).unwrap(), IntegerData::minus_one());
+        assert_eq!(parse("7ff", 12).unwrap(), IntegerData::from_i32(2047));
+        assert_eq!(parse("3f", 6).unwrap(), IntegerData::minus_one());
+        assert_eq!(parse("1f", 6).unwrap(), IntegerData::from_i32(31));
+        assert_eq!(exception_code(parse("zz", 8)), Some(ExceptionCode::TypeCheckError));
+        assert_eq!(exception_code(parse("f", 8)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(parse("ff", 6)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(parse("", 0)), Some(ExceptionCode::RangeCheckError));
+    }
+
+        #[test]
+        fn test_to_byte_groups_wide() {
+            let groups = IntegerData::one().to_byte_groups(11, 33).unwrap();
+            assert_eq!(groups, vec![IntegerData::zero(), IntegerData::zero(), IntegerData::one()]);
+            assert_eq!(exception_code(IntegerData::one().to_byte_groups(1, 34)), Some(ExceptionCode::RangeCheckError));
+            let huge = usize::MAX - usize::MAX % 8;
+            assert_eq!(exception_code(IntegerData::one().to_byte_groups(8, huge)), Some(ExceptionCode::RangeCheckError));
+        }
+}
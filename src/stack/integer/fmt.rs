@@ -11,17 +11,22 @@
 * limitations under the License.
 */
 
+use crate::{error::TvmError, types::Exception};
 use super::{
+    Int,
     IntegerData,
     IntegerValue,
+    utils::process_value,
 };
+use num_traits::{One, Signed};
 use std::fmt;
+use ton_types::{error, ExceptionCode, Result};
 
 impl IntegerData {
     /// Converts value into String with given radix.
     pub fn to_str_radix(&self, radix: u32) -> String {
         match self.value {
-            IntegerValue::NaN => "NaN".to_string(),
+            IntegerValue::NaN(_) => "NaN".to_string(),
             IntegerValue::Value(ref value) => value.to_str_radix(radix),
         }
     }
@@ -30,6 +35,60 @@ impl IntegerData {
     pub fn to_str(&self) -> String {
         self.to_str_radix(10)
     }
+
+    /// Converts value into decimal String padded with zeros to at least min_digits digits.
+    /// Sign is placed before the padding.
+    pub fn to_zero_padded_decimal(&self, min_digits: usize) -> String {
+        match self.value {
+            IntegerValue::NaN(_) => "NaN".to_string(),
+            IntegerValue::Value(ref value) => {
+                let sign = if value.is_negative() { "-" } else { "" };
+                format!("{}{:0>width$}", sign, value.magnitude(), width = min_digits)
+            }
+        }
+    }
+
+    /// Converts value into lowercase hex String of its two's complement representation
+    /// in a given bits size, zero-padded to the full width (e.g. -1 in 8 bits is "ff").
+    /// Widths above 257 bits raise IntegerOverflow.
+    pub fn to_twos_complement_hex(&self, bits: usize) -> Result<String> {
+        if bits > 257 {
+            return err!(ExceptionCode::IntegerOverflow, "{} bits are wider than any value", bits)
+        }
+        if bits == 0 || !self.fits_in(bits)? {
+            return err!(ExceptionCode::RangeCheckError, "value does not fit into {} signed bits", bits)
+        }
+        process_value(self, |value| {
+            let mask = (Int::one() << bits) - 1;
+            let digits = bits.div_ceil(4);
+            Ok(format!("{:0>width$}", (value & mask).to_str_radix(16), width = digits))
+        })
+    }
+
+    /// Dumps internal representation: sign, bit length and little-endian u32 limbs in hex.
+    /// Intended for diagnostics only.
+    pub fn debug_repr(&self) -> String {
+        match self.value {
+            IntegerValue::NaN(ref reason) => format!("NaN {:?}", reason),
+            IntegerValue::Value(ref value) => {
+                let (sign, limbs) = value.to_u32_digits();
+                format!("{:?} bits={} limbs={:x?}", sign, value.bits(), limbs)
+            }
+        }
+    }
+
+    /// Converts value into JSON as TON ABI does: number if it fits into i64,
+    /// decimal string otherwise, and null for NaN.
+    #[cfg(feature = "serde")]
+    pub fn to_abi_json(&self) -> serde_json::Value {
+        match self.value {
+            IntegerValue::NaN(_) => serde_json::Value::Null,
+            IntegerValue::Value(ref value) => match num::ToPrimitive::to_i64(value) {
+                Some(value) => serde_json::Value::from(value),
+                None => serde_json::Value::String(value.to_str_radix(10)),
+            }
+        }
+    }
 }
 
 impl fmt::Display for IntegerData {
@@ -55,3 +114,63 @@ impl fmt::Binary for IntegerData {
         write!(f, "{}", self.to_str_radix(2))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::integer::tests::exception_code;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_to_abi_json() {
+        assert_eq!(IntegerData::from_i32(42).to_abi_json(), serde_json::Value::from(42));
+        assert_eq!(IntegerData::from_i32(-42).to_abi_json(), serde_json::Value::from(-42));
+        let large = Int::one() << 200;
+        let expected = serde_json::Value::String(large.to_str_radix(10));
+        assert_eq!(IntegerData::from(large).unwrap().to_abi_json(), expected);
+        assert_eq!(IntegerData::nan().to_abi_json(), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_debug_repr() {
+        assert_eq!(IntegerData::minus_one().debug_repr(), "Minus bits=1 limbs=[1]");
+        assert_eq!(IntegerData::zero().debug_repr(), "NoSign bits=0 limbs=[]");
+        assert_eq!(IntegerData::from_u64(0x1_0000_00ff).debug_repr(), "Plus bits=33 limbs=[ff, 1]");
+        assert!(IntegerData::nan().debug_repr().starts_with("NaN"));
+    }
+
+    #[test]
+    fn test_to_zero_padded_decimal() {
+        assert_eq!(IntegerData::from_i32(42).to_zero_padded_decimal(5), "00042");
+        assert_eq!(IntegerData::from_i32(-42).to_zero_padded_decimal(5), "-00042");
+        assert_eq!(IntegerData::from_i32(123456).to_zero_padded_decimal(3), "123456");
+        assert_eq!(IntegerData::zero().to_zero_padded_decimal(0), "0");
+        assert_eq!(IntegerData::nan().to_zero_padded_decimal(5), "NaN");
+    }
+
+    #[test]
+    fn test_to_twos_complement_hex() {
+        let hex = |x: i32, bits: usize| IntegerData::from_i32(x).to_twos_complement_hex(bits).unwrap();
+        assert_eq!(hex(-1, 8), "ff");
+        assert_eq!(hex(5, 12), "005");
+        assert_eq!(hex(-2, 4), "e");
+        assert_eq!(hex(-128, 8), "80");
+        assert_eq!(hex(127, 8), "7f");
+        assert_eq!(hex(-1, 6), "3f");
+    }
+
+    #[test]
+    fn test_to_twos_complement_hex_errors() {
+        let code = |value: IntegerData, bits: usize| exception_code(value.to_twos_complement_hex(bits));
+        assert_eq!(code(IntegerData::from_i32(255), 8), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(code(IntegerData::zero(), 0), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(code(IntegerData::nan(), 8), Some(ExceptionCode::IntegerOverflow));
+    }
+
+        #[test]
+        fn test_to_twos_complement_hex_wide() {
+            assert_eq!(IntegerData::minus_one().to_twos_complement_hex(257).unwrap(), format!("1{}", "f".repeat(64)));
+            assert_eq!(exception_code(IntegerData::minus_one().to_twos_complement_hex(258)), Some(ExceptionCode::IntegerOverflow));
+            assert_eq!(exception_code(IntegerData::zero().to_twos_complement_hex(usize::MAX)), Some(ExceptionCode::IntegerOverflow));
+        }
+}
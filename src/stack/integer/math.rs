@@ -13,15 +13,21 @@
 
 use std::ops::AddAssign;
 
-use crate::stack::integer::{
-    IntegerData, IntegerValue, behavior::OperationBehavior,
-    utils::{
-        binary_op, construct_double_nan, construct_single_nan, process_double_result,
-        process_single_result, unary_op
-    }
+use crate::{
+    error::TvmError,
+    stack::integer::{
+        Int, IntegerData, IntegerValue,
+        behavior::{BehaviorKind, OperationBehavior, Quiet, Signaling},
+        utils::{
+            binary_op, bitsize, check_overflow, construct_double_nan, construct_single_nan,
+            process_double_result, process_single_result, process_value, unary_op
+        }
+    },
+    types::{Exception, ResultOpt},
 };
-use num_traits::Zero;
-use ton_types::{Result, Status};
+use num_traits::{One, Signed, Zero};
+use std::cmp::Ordering;
+use ton_types::{error, ExceptionCode, Result, Status};
 
 // [x / y] -> (q, r)  :  q*y + r = x  :  |r| < |y|
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -32,7 +38,48 @@ pub enum Round {
     Nearest = 2,                 // | r |   =<   | y/2 |
 }
 
+/// Unary operation dispatched by IntegerData::apply_unary()
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Abs,
+    Not,
+    Inc,
+    Dec,
+    Signum,
+}
+
+/// Sign convention of remainder used by IntegerData::rem_with_sign()
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum RemSign {
+    Dividend,    // r has the same sign as x (truncating division)
+    Divisor,     // r has the same sign as y (floor division)
+    NonNegative, // r is in [0, |y|) (Euclidean division)
+}
+
 impl IntegerData {
+    /// Applies a given unary operation to value taking in account behavior of operation.
+    pub fn apply_unary<T: OperationBehavior>(op: UnaryOp, value: &IntegerData) -> Result<IntegerData> {
+        match op {
+            UnaryOp::Neg => value.neg::<T>(),
+            UnaryOp::Abs => unary_op::<T, _, _, _, _, _>(
+                value,
+                |x| x.abs(),
+                construct_single_nan,
+                process_single_result::<T, _>
+            ),
+            UnaryOp::Not => value.not::<T>(),
+            UnaryOp::Inc => value.add_i8::<T>(&1),
+            UnaryOp::Dec => value.sub_i8::<T>(&1),
+            UnaryOp::Signum => unary_op::<T, _, _, _, _, _>(
+                value,
+                |x| x.signum(),
+                construct_single_nan,
+                process_single_result::<T, _>
+            ),
+        }
+    }
+
     /// Creates and returns a copy of the same value with a sign changed to an opposite.
     pub fn neg<T: OperationBehavior>(&self) -> Result<IntegerData> {
         unary_op::<T, _, _, _, _, _>(
@@ -43,6 +90,21 @@ impl IntegerData {
         )
     }
 
+    /// Creates and returns a copy of the same absolute value with a given sign.
+    /// Zero stays zero regardless of requested sign.
+    pub fn with_sign<T: OperationBehavior>(&self, negative: bool) -> Result<IntegerData> {
+        unary_op::<T, _, _, _, _, _>(
+            self,
+            |x| if negative {
+                -x.abs()
+            } else {
+                x.abs()
+            },
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
+
     pub fn add<T: OperationBehavior>(&self, other: &IntegerData) -> Result<IntegerData> {
         binary_op::<T, _, _, _, _, _>(
             self,
@@ -53,9 +115,29 @@ impl IntegerData {
         )
     }
 
+    /// Adds values using behavior chosen at runtime.
+    pub fn add_dyn(&self, other: &IntegerData, kind: BehaviorKind) -> Result<IntegerData> {
+        match kind {
+            BehaviorKind::Quiet => self.add::<Quiet>(other),
+            BehaviorKind::Signaling => self.add::<Signaling>(other),
+        }
+    }
+
+    /// Estimates upper bound of signed bit length of self + other without computing it.
+    /// Returns 0 for NaN in quiet mode.
+    pub fn result_bits_add<T: OperationBehavior>(&self, other: &IntegerData) -> Result<usize> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| std::cmp::max(bitsize(x), bitsize(y)) + 1,
+            || 0,
+            |result, _| Ok(result)
+        )
+    }
+
     pub fn add_assign<T: OperationBehavior>(&mut self, other: &IntegerData) -> Status {
         let lhs = match self.value {
-            IntegerValue::NaN => {
+            IntegerValue::NaN(_) => {
                 on_nan_parameter!(T)?;
                 *self = construct_single_nan();
                 return Ok(())
@@ -63,7 +145,7 @@ impl IntegerData {
             IntegerValue::Value(ref mut v) => v,
         };
         let rhs = match other.value {
-            IntegerValue::NaN => {
+            IntegerValue::NaN(_) => {
                 on_nan_parameter!(T)?;
                 *self = construct_single_nan();
                 return Ok(())
@@ -71,7 +153,7 @@ impl IntegerData {
             IntegerValue::Value(ref v) => v,
         };
         lhs.add_assign(rhs);
-        if !super::utils::check_overflow(lhs) {
+        if !check_overflow(lhs) {
             on_integer_overflow!(T)?;
             *self = construct_single_nan();
         }
@@ -87,6 +169,17 @@ impl IntegerData {
         )
     }
 
+    /// Computes floor((self + other) / 2) without overflowing the intermediate sum.
+    pub fn midpoint<T: OperationBehavior>(&self, other: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| (x + y) >> 1,
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
+
     pub fn sub<T: OperationBehavior>(&self, other: &IntegerData) -> Result<IntegerData> {
         binary_op::<T, _, _, _, _, _>(
             self,
@@ -97,6 +190,17 @@ impl IntegerData {
         )
     }
 
+    /// Computes |self - other| in a single step.
+    pub fn abs_diff<T: OperationBehavior>(&self, other: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| (x - y).abs(),
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
+
     pub fn sub_i8<T: OperationBehavior>(&self, other: &i8) -> Result<IntegerData> {
         unary_op::<T, _, _, _, _, _>(
             self,
@@ -116,6 +220,80 @@ impl IntegerData {
         )
     }
 
+    /// Checks if product of values would overflow. Estimates bit length of the product
+    /// and computes it only near the boundary.
+    pub fn would_mul_overflow<T: OperationBehavior>(&self, other: &IntegerData) -> Result<bool> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| {
+                if x.is_zero() || y.is_zero() {
+                    return false
+                }
+                // product of a-bit and b-bit magnitudes takes a + b - 1 or a + b bits
+                let bits = x.bits() + y.bits();
+                if bits <= 256 {
+                    false
+                } else if bits - 1 > 257 {
+                    true
+                } else {
+                    !check_overflow(&(x * y))
+                }
+            },
+            || false,
+            |result, _| Ok(result)
+        )
+    }
+
+    /// Multiplies value by 10^exp for positive exp or floor-divides it by 10^(-exp) for negative one.
+    pub fn scale_pow10<T: OperationBehavior>(&self, exp: i32) -> Result<IntegerData> {
+        // 10^80 exceeds any 257-bit value, so greater powers change nothing but the cost
+        let power = num::pow::pow(Int::from(10u32), exp.unsigned_abs().min(80) as usize);
+        unary_op::<T, _, _, _, _, _>(
+            self,
+            |x| if exp >= 0 {
+                x * &power
+            } else {
+                num::Integer::div_floor(x, &power)
+            },
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
+
+    /// Rounds value to a given count of significant decimal digits.
+    pub fn round_sig_figs(&self, figs: u32, rounding: Round) -> Result<IntegerData> {
+        if figs == 0 {
+            return err!(ExceptionCode::RangeCheckError, "at least one significant digit is required")
+        }
+        process_value(self, |value| {
+            let digits = value.magnitude().to_str_radix(10).len();
+            if digits <= figs as usize {
+                return Ok(self.clone())
+            }
+            let power = num::pow::pow(Int::from(10u32), digits - figs as usize);
+            let (quotient, _) = utils::divmod(value, &power, rounding);
+            IntegerData::from(quotient * power)
+        })
+    }
+
+    /// Computes reciprocal of value scaled by 10^decimals, i.e. 10^decimals / value
+    /// rounded in a given way.
+    pub fn reciprocal_fixed(&self, decimals: u32, rounding: Round) -> Result<IntegerData> {
+        process_value(self, |value| {
+            if value.is_zero() {
+                return err!(ExceptionCode::IntegerOverflow, "division by zero")
+            }
+            // 10^160 / |value| never fits into 257 bits, so don't spend time on greater powers
+            if decimals > 160 {
+                return err!(ExceptionCode::IntegerOverflow, "integer overflow")
+            }
+            let power = num::pow::pow(Int::from(10u32), decimals as usize);
+            let (quotient, _) = utils::divmod(&power, value, rounding);
+            IntegerData::from(quotient)
+        })
+    }
+
     pub fn mul_shr256<T: OperationBehavior>(&self, other: &IntegerData) -> Result<IntegerData> {
         binary_op::<T, _, _, _, _, _>(
             self,
@@ -141,7 +319,11 @@ impl IntegerData {
         let divisor = extract_value!(T, divisor, construct_double_nan);
         if divisor.is_zero() {
             on_integer_overflow!(T)?;
-            return Ok(construct_double_nan());
+            let (quotient, remainder) = construct_double_nan();
+            return Ok((
+                quotient.with_nan_reason("division by zero"),
+                remainder.with_nan_reason("division by zero")
+            ));
         }
 
         unary_op::<T, _, _, _, _, _>(
@@ -152,6 +334,166 @@ impl IntegerData {
         )
     }
 
+    /// Computes remainder of Euclidean division which is always in [0, |d|).
+    pub fn rem_euclid<T: OperationBehavior>(&self, divisor: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            divisor,
+            |x, y| if y.is_zero() {
+                None
+            } else {
+                Some(num::Integer::mod_floor(x, &y.abs()))
+            },
+            construct_single_nan,
+            process_division_result::<T, _>
+        )
+    }
+
+    /// Computes remainder in [0, |m|) regardless of signs of value and modulus.
+    /// This is the same as rem_euclid().
+    #[inline]
+    pub fn mod_positive<T: OperationBehavior>(&self, modulus: &IntegerData) -> Result<IntegerData> {
+        self.rem_euclid::<T>(modulus)
+    }
+
+    /// Computes remainder of division with a given sign convention.
+    pub fn rem_with_sign<T: OperationBehavior>(&self, divisor: &IntegerData, sign: RemSign) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            divisor,
+            |x, y| if y.is_zero() {
+                None
+            } else {
+                Some(match sign {
+                    RemSign::Dividend => x % y,
+                    RemSign::Divisor => num::Integer::mod_floor(x, y),
+                    RemSign::NonNegative => num::Integer::mod_floor(x, &y.abs()),
+                })
+            },
+            construct_single_nan,
+            process_division_result::<T, _>
+        )
+    }
+
+    /// Converts value into mixed radix digits successively dividing it by each radix.
+    /// Returns remainders in order of radices followed by the final quotient.
+    pub fn to_mixed_radix(&self, radices: &[IntegerData]) -> Result<Vec<IntegerData>> {
+        process_value(self, |value| {
+            let mut quotient = value.clone();
+            let mut digits = Vec::with_capacity(radices.len() + 1);
+            for radix in radices {
+                let radix = process_value(radix, |radix| Ok(radix.clone()))?;
+                if !radix.is_positive() {
+                    return err!(ExceptionCode::RangeCheckError, "radix {} must be positive", radix)
+                }
+                let (next, digit) = num::Integer::div_mod_floor(&quotient, &radix);
+                digits.push(IntegerData::from(digit)?);
+                quotient = next;
+            }
+            digits.push(IntegerData::from(quotient)?);
+            Ok(digits)
+        })
+    }
+
+    /// Reconstructs value from mixed radix digits produced by to_mixed_radix():
+    /// digits go in order of radices followed by the final quotient.
+    pub fn from_mixed_radix(digits: &[IntegerData], radices: &[IntegerData]) -> Result<IntegerData> {
+        let (quotient, digits) = match digits.split_last() {
+            Some(split) if digits.len() == radices.len() + 1 => split,
+            _ => return err!(
+                ExceptionCode::RangeCheckError,
+                "{} digits do not match {} radices", digits.len(), radices.len()
+            )
+        };
+        let mut value = process_value(quotient, |quotient| Ok(quotient.clone()))?;
+        for (digit, radix) in digits.iter().zip(radices).rev() {
+            value = process_value(digit, |digit| process_value(radix, |radix| {
+                if digit.is_negative() || digit >= radix {
+                    return err!(ExceptionCode::RangeCheckError, "digit {} is out of radix {}", digit, radix)
+                }
+                Ok(&value * radix + digit)
+            }))?;
+        }
+        IntegerData::from(value)
+    }
+
+    /// Computes quotient of Euclidean division, so that value == div_euclid * d + rem_euclid.
+    pub fn div_euclid<T: OperationBehavior>(&self, divisor: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            divisor,
+            |x, y| if y.is_zero() {
+                None
+            } else {
+                Some((x - num::Integer::mod_floor(x, &y.abs())) / y)
+            },
+            construct_single_nan,
+            process_division_result::<T, _>
+        )
+    }
+
+    /// Computes sum(values[i] * weights[i]) / sum(weights[i]) in full precision
+    /// with a given rounding.
+    pub fn weighted_average<T: OperationBehavior>(
+        values: &[IntegerData],
+        weights: &[IntegerData],
+        rounding: Round
+    ) -> Result<IntegerData> {
+        if values.len() != weights.len() {
+            return err!(
+                ExceptionCode::RangeCheckError,
+                "{} values do not match {} weights", values.len(), weights.len()
+            )
+        }
+        let mut sum = Int::zero();
+        let mut total_weight = Int::zero();
+        for (value, weight) in values.iter().zip(weights) {
+            match (&value.value, &weight.value) {
+                (IntegerValue::Value(value), IntegerValue::Value(weight)) => {
+                    sum += value * weight;
+                    total_weight += weight;
+                }
+                _ => {
+                    on_nan_parameter!(T)?;
+                    return Ok(construct_single_nan())
+                }
+            }
+        }
+        if total_weight.is_zero() {
+            on_integer_overflow!(T)?;
+            return Ok(construct_single_nan().with_nan_reason("division by zero"))
+        }
+        let (quotient, _) = utils::divmod(&sum, &total_weight, rounding);
+        process_single_result::<T, _>(quotient, construct_single_nan)
+    }
+
+    /// Returns floored quotient and whether the division was exact (remainder is zero).
+    pub fn div_exact<T: OperationBehavior>(&self, divisor: &IntegerData) -> Result<(IntegerData, bool)> {
+        let (quotient, remainder) = self.div::<T>(divisor, Round::FloorToNegativeInfinity)?;
+        Ok((quotient, remainder.is_zero()))
+    }
+
+    /// Checks if value is divisible by a given divisor.
+    pub fn is_divisible_by<T: OperationBehavior>(&self, divisor: &IntegerData) -> Result<bool> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            divisor,
+            |x, y| if y.is_zero() {
+                None
+            } else {
+                Some(num::Integer::is_multiple_of(x, y))
+            },
+            || false,
+            |result, nan_constructor| match result {
+                Some(result) => Ok(result),
+                None => {
+                    on_integer_overflow!(T)?;
+                    Ok(nan_constructor())
+                }
+            }
+        )
+    }
+
     pub fn div_by_shift<T: OperationBehavior>(&self, shift: usize, rounding: Round)
                                               -> Result<(IntegerData, IntegerData)>
     {
@@ -162,127 +504,1914 @@ impl IntegerData {
             process_double_result::<T, _>
         )
     }
-}
 
-pub mod utils {
+    /// Multiplies fixed point values with frac_bits fractional bits: (x * y) >> frac_bits.
+    /// The product is kept in full precision, only the rounded result is checked for overflow.
+    pub fn fixed_mul<T: OperationBehavior>(&self, other: &IntegerData, frac_bits: usize, rounding: Round)
+                                           -> Result<IntegerData>
+    {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| utils::div_by_shift(&(x * y), frac_bits, rounding).0,
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
 
-    use crate::stack::integer::{Int, math::Round};
-    use num_traits::{One, Signed, Zero};
-    use std::cmp::Ordering;
+    /// Divides fixed point values with frac_bits fractional bits: (x << frac_bits) / y.
+    pub fn fixed_div<T: OperationBehavior>(&self, other: &IntegerData, frac_bits: usize, rounding: Round)
+                                           -> Result<IntegerData>
+    {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            |x, y| if y.is_zero() {
+                None
+            } else {
+                Some(utils::divmod(&(x << frac_bits), y, rounding).0)
+            },
+            construct_single_nan,
+            process_division_result::<T, _>
+        )
+    }
 
-    #[inline]
-    pub fn divmod(dividend: &Int, divisor: &Int, rounding: Round) -> (Int, Int) {
-        match rounding {
-            Round::FloorToNegativeInfinity =>
-                num::Integer::div_mod_floor(dividend, divisor),
-            Round::FloorToZero =>
-                num::Integer::div_rem(dividend, divisor),
-            Round::Ceil => {
-                let (mut quotient, mut remainder) = num::Integer::div_rem(dividend, divisor);
-                round_ceil(&mut quotient, &mut remainder, dividend, divisor);
-                (quotient, remainder)
-            }
-            Round::Nearest => {
-                let (mut quotient, mut remainder) = num::Integer::div_rem(dividend, divisor);
-                round_nearest(&mut quotient, &mut remainder, dividend, divisor);
-                (quotient, remainder)
+    /// Clamps value into signed range [-2^(bits-1), 2^(bits-1)-1]. NaN is kept as is.
+    pub fn saturate_signed(&self, bits: usize) -> Result<IntegerData> {
+        if bits == 0 {
+            return err!(ExceptionCode::RangeCheckError, "cannot saturate into 0 bits")
+        }
+        match self.value {
+            IntegerValue::NaN(_) => Ok(construct_single_nan()),
+            IntegerValue::Value(ref value) => {
+                // values are within 257 bits, so wider bounds never clamp
+                let bits = bits.min(258);
+                let max = (Int::one() << (bits - 1)) - 1;
+                let min = -(Int::one() << (bits - 1));
+                if value > &max {
+                    IntegerData::from(max)
+                } else if value < &min {
+                    IntegerData::from(min)
+                } else {
+                    Ok(self.clone())
+                }
             }
         }
     }
 
-    #[inline]
-    pub fn div_by_shift(dividend: &Int, shift: usize, rounding: Round) -> (Int, Int) {
-        let divisor = Int::one() << shift;
-        let remainder_mask = divisor.clone() - 1;
-        let (mut quotient, mut remainder) = if dividend.sign() == num::bigint::Sign::Minus {
-            let dividend = dividend.abs();
-            (-(dividend.clone() >> shift), -(dividend & &remainder_mask))
-        } else {
-            (dividend >> shift, dividend & &remainder_mask)
-        };
-        match rounding {
-            Round::FloorToNegativeInfinity =>
-                round_floor_to_negative_infinity(
-                    &mut quotient, &mut remainder, dividend, &divisor
-                ),
-            Round::Ceil =>
-                round_ceil(&mut quotient, &mut remainder, dividend, &divisor),
-            Round::Nearest =>
-                round_nearest(&mut quotient, &mut remainder, dividend, &divisor),
-            _ => {}
+    /// Clamps value into unsigned range [0, 2^bits - 1]. NaN is kept as is.
+    pub fn saturate_unsigned(&self, bits: usize) -> Result<IntegerData> {
+        match self.value {
+            IntegerValue::NaN(_) => Ok(construct_single_nan()),
+            IntegerValue::Value(ref value) => {
+                // values are below 2^256, so wider bounds never clamp
+                let max = (Int::one() << bits.min(258)) - 1;
+                if value > &max {
+                    IntegerData::from(max)
+                } else if value.is_negative() {
+                    Ok(IntegerData::zero())
+                } else {
+                    Ok(self.clone())
+                }
+            }
         }
-        (quotient, remainder)
     }
 
-    #[inline]
-    fn round_floor_to_negative_infinity(
-        quotient: &mut Int,
-        remainder: &mut Int,
-        dividend: &Int,
-        divisor: &Int,
-    ) {
-        if remainder.is_zero() || remainder.sign() == divisor.sign() {
-            // No rounding needed
-            return;
-        }
-        *remainder += divisor;
-        if dividend.sign() == divisor.sign() {
-            *quotient += 1;
-        } else {
-            *quotient -= 1;
+    /// Quantizes value as if it was stored with a given count of significant bits
+    /// (including sign bit for signed values): dropped low bits are rounded
+    /// and then the kept ones are clamped into the representable range.
+    pub fn quantize(&self, bits: usize, signed: bool, rounding: Round) -> Result<IntegerData> {
+        if bits == 0 {
+            return err!(ExceptionCode::RangeCheckError, "cannot quantize into 0 bits")
         }
+        process_value(self, |value| {
+            if !signed && value.is_negative() {
+                return Ok(IntegerData::zero())
+            }
+            let mantissa_bits = if signed { bits - 1 } else { bits };
+            let width = value.bits() as usize;
+            if width <= mantissa_bits {
+                return IntegerData::from(value.clone())
+            }
+            let mut shift = width - mantissa_bits;
+            let (mut mantissa, _) = utils::div_by_shift(value, shift, rounding);
+            let max = (Int::one() << mantissa_bits) - 1;
+            // rounding may carry into 2^mantissa_bits, which is kept exact by one more shift
+            if mantissa_bits > 0 && mantissa > max {
+                mantissa >>= 1;
+                shift += 1;
+            }
+            let min = if signed { -(Int::one() << mantissa_bits) } else { Int::zero() };
+            if mantissa > max {
+                mantissa = max;
+            } else if mantissa < min {
+                mantissa = min;
+            }
+            IntegerData::from(mantissa << shift)
+        })
     }
 
-    #[inline]
-    fn round_ceil(
-        quotient: &mut Int,
-        remainder: &mut Int,
-        dividend: &Int,
-        divisor: &Int,
-    ) {
-        if remainder.is_zero() || remainder.sign() != divisor.sign() {
-            // No rounding needed
-            return;
+    /// Computes ceil(value / 2^n) for non-negative value.
+    pub fn ceil_div_pow2(&self, n: usize) -> Result<IntegerData> {
+        self.check_neg()?;
+        process_value(self, |value| {
+            // value is below 2^256, so any wider divisor gives 1 for positive value
+            if n > 256 {
+                return Ok(if value.is_zero() { IntegerData::zero() } else { IntegerData::one() })
+            }
+            let mask = (Int::one() << n) - 1;
+            IntegerData::from((value + mask) >> n)
+        })
+    }
+
+    /// Computes value mod (2^p_bits - 1) for non-negative value by folding high bits
+    /// onto low ones.
+    pub fn mod_mersenne(&self, p_bits: usize) -> Result<IntegerData> {
+        if p_bits == 0 {
+            return err!(ExceptionCode::IntegerOverflow, "division by zero")
         }
-        *remainder -= divisor;
-        if dividend.sign() == divisor.sign() {
-            *quotient += 1;
-        } else {
-            *quotient -= 1;
+        self.check_neg()?;
+        // 2^257 - 1 already exceeds any value
+        if p_bits > 256 {
+            return Ok(self.clone())
         }
+        process_value(self, |value| {
+            let modulus = (Int::one() << p_bits) - 1;
+            let mut result = value.clone();
+            while result > modulus {
+                result = (&result & &modulus) + (&result >> p_bits);
+            }
+            if result == modulus {
+                result.set_zero();
+            }
+            IntegerData::from(result)
+        })
     }
 
-    #[inline]
-    fn round_nearest(
-        quotient: &mut Int,
-        remainder: &mut Int,
-        dividend: &Int,
-        divisor: &Int,
-    ) {
-        if remainder.is_zero() {
-            // No rounding needed
-            return;
+    /// Represents Q-format value with a given count of fractional bits
+    /// as a fraction (numerator, denominator) reduced by gcd.
+    /// More than 256 fractional bits raise IntegerOverflow.
+    pub fn to_ratio(&self, frac_bits: usize) -> Result<(IntegerData, IntegerData)> {
+        if frac_bits > 256 {
+            return err!(ExceptionCode::IntegerOverflow, "denominator 2^{} cannot fit in 257 bits", frac_bits)
         }
-        //  5 / 2  ->   2,  1  ->   3, -1
-        // -5 / 2  ->  -2, -1  ->  -2, -1
-        //  5 /-2  ->  -2,  1  ->  -2,  1
-        // -5 /-2  ->   2, -1  ->   3,  1
-        let r_x2: Int = remainder.clone() << 1;
-        let cmp_result = r_x2.abs().cmp(&divisor.abs());
-        let is_not_negative = dividend.sign() == divisor.sign();
-        if cmp_result == Ordering::Greater
-            || (cmp_result == Ordering::Equal && is_not_negative)
-        {
-            if divisor.sign() == remainder.sign() {
-                *remainder -= divisor;
-            } else {
-                *remainder += divisor;
+        process_value(self, |value| {
+            let denominator = Int::one() << frac_bits;
+            let gcd = num::Integer::gcd(value, &denominator);
+            Ok((IntegerData::from(value / &gcd)?, IntegerData::from(denominator / gcd)?))
+        })
+    }
+
+    /// Reduces fraction numerator / denominator by gcd so that denominator is positive.
+    pub fn reduce_fraction(numerator: &IntegerData, denominator: &IntegerData)
+                           -> Result<(IntegerData, IntegerData)>
+    {
+        process_value(numerator, |n| process_value(denominator, |d| {
+            if d.is_zero() {
+                return err!(ExceptionCode::IntegerOverflow, "division by zero")
             }
-            if is_not_negative {
-                *quotient += 1;
-            } else {
-                *quotient -= 1;
+            let mut gcd = num::Integer::gcd(n, d);
+            if d.is_negative() {
+                gcd = -gcd;
             }
-        }
+            Ok((IntegerData::from(n / &gcd)?, IntegerData::from(d / gcd)?))
+        }))
     }
+
+    /// Approximates fraction value / denom by the closest fraction p / q with 0 < q <= max_denom
+    /// using continued fraction convergents and semiconvergents.
+    pub fn rational_approx(&self, denom: &IntegerData, max_denom: &IntegerData)
+                           -> Result<(IntegerData, IntegerData)>
+    {
+        let mut n = process_value(self, |value| Ok(value.clone()))?;
+        let mut d = process_value(denom, |denom| Ok(denom.clone()))?;
+        let max_denom = process_value(max_denom, |max_denom| Ok(max_denom.clone()))?;
+        if d.is_zero() {
+            return err!(ExceptionCode::IntegerOverflow, "division by zero")
+        }
+        if max_denom < Int::one() {
+            return err!(ExceptionCode::RangeCheckError, "max denominator {} must be positive", max_denom)
+        }
+        if d.is_negative() {
+            n = -n;
+            d = -d;
+        }
+        let (numerator, denominator) = (n.clone(), d.clone());
+        let (mut p0, mut q0, mut p1, mut q1) = (Int::zero(), Int::one(), Int::one(), Int::zero());
+        loop {
+            let (a, r) = num::Integer::div_mod_floor(&n, &d);
+            let q2 = &q0 + &a * &q1;
+            if q2 > max_denom {
+                break
+            }
+            let p2 = &p0 + &a * &p1;
+            p0 = std::mem::replace(&mut p1, p2);
+            q0 = std::mem::replace(&mut q1, q2);
+            n = std::mem::replace(&mut d, r);
+            if d.is_zero() {
+                return Ok((IntegerData::from(p1)?, IntegerData::from(q1)?))
+            }
+        }
+        // best semiconvergent with denominator not greater than max_denom
+        let k = (&max_denom - &q0) / &q1;
+        let p = &p0 + &k * &p1;
+        let q = &q0 + &k * &q1;
+        // compare |p1 / q1 - numerator / denominator| with |p / q - numerator / denominator|
+        let convergent_error = (&p1 * &denominator - &numerator * &q1).abs() * &q;
+        let semiconvergent_error = (&p * &denominator - &numerator * &q).abs() * &q1;
+        if convergent_error <= semiconvergent_error {
+            Ok((IntegerData::from(p1)?, IntegerData::from(q1)?))
+        } else {
+            Ok((IntegerData::from(p)?, IntegerData::from(q)?))
+        }
+    }
+
+    /// Expands fraction numerator / denominator into continued fraction [a0; a1, a2, ...]
+    /// returning at most max_terms coefficients.
+    pub fn continued_fraction(numerator: &IntegerData, denominator: &IntegerData, max_terms: usize)
+                              -> Result<Vec<IntegerData>>
+    {
+        process_value(numerator, |n| process_value(denominator, |d| {
+            if d.is_zero() {
+                return err!(ExceptionCode::IntegerOverflow, "division by zero")
+            }
+            let (mut n, mut d) = if d.is_negative() { (-n, -d) } else { (n.clone(), d.clone()) };
+            let mut terms = Vec::new();
+            while terms.len() < max_terms && !d.is_zero() {
+                let (term, remainder) = num::Integer::div_mod_floor(&n, &d);
+                terms.push(IntegerData::from(term)?);
+                n = std::mem::replace(&mut d, remainder);
+            }
+            Ok(terms)
+        }))
+    }
+
+    /// Returns representative in [-2^(n-1), 2^(n-1)) congruent to value modulo 2^n.
+    pub fn reduce_mod_pow2_signed(&self, n: usize) -> Result<IntegerData> {
+        // any value fits into [-2^257, 2^257), so greater n change nothing but the cost
+        let n = n.min(258);
+        process_value(self, |value| {
+            if n == 0 {
+                return Ok(IntegerData::zero())
+            }
+            let modulus = Int::one() << n;
+            let mut result = num::Integer::mod_floor(value, &modulus);
+            if result >= Int::one() << (n - 1) {
+                result -= modulus;
+            }
+            IntegerData::from(result)
+        })
+    }
+
+    /// Raises value to a given power. Negative exponent raises range check error,
+    /// exponent clearly leading to overflow is rejected before the computation.
+    pub fn checked_pow_big<T: OperationBehavior>(&self, exp: &IntegerData) -> Result<IntegerData> {
+        let exp = extract_value!(T, exp, construct_single_nan);
+        if exp.is_negative() {
+            on_range_check_error!(T)?;
+            return Ok(construct_single_nan())
+        }
+        unary_op::<T, _, _, _, _, _>(
+            self,
+            |base| utils::checked_pow(base, exp),
+            construct_single_nan,
+            |result, nan_constructor| match result {
+                Some(result) => process_single_result::<T, _>(result, nan_constructor),
+                None => {
+                    on_integer_overflow!(T)?;
+                    Ok(nan_constructor().with_nan_reason("integer overflow"))
+                }
+            }
+        )
+    }
+
+    /// Computes value^exp mod 2^n keeping all intermediates masked to n bits.
+    /// Widths above 258 are treated as 258, which keeps every result that fits into 257 bits.
+    pub fn pow_mod_pow2<T: OperationBehavior>(&self, exp: &IntegerData, n: usize) -> Result<IntegerData> {
+        // a result fitting into 257 bits is the same modulo 2^n and 2^258
+        let n = n.min(258);
+        let exp = extract_value!(T, exp, construct_single_nan);
+        if exp.is_negative() {
+            on_range_check_error!(T)?;
+            return Ok(construct_single_nan())
+        }
+        unary_op::<T, _, _, _, _, _>(
+            self,
+            |base| utils::pow_mod_pow2(base, exp, n),
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
+
+    /// Computes n! taking in account behavior of operation on overflow.
+    pub fn factorial<T: OperationBehavior>(n: u32) -> Result<IntegerData> {
+        let mut result = Int::one();
+        for i in 2..=n {
+            result *= i;
+            if !check_overflow(&result) {
+                on_integer_overflow!(T)?;
+                return Ok(construct_single_nan().with_nan_reason("integer overflow"))
+            }
+        }
+        IntegerData::from(result)
+    }
+
+    /// Computes binomial coefficient C(n, k) taking in account behavior of operation.
+    pub fn binomial<T: OperationBehavior>(n: &IntegerData, k: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            n,
+            k,
+            utils::binomial,
+            construct_single_nan,
+            |result, nan_constructor| match result {
+                Some(result) => process_single_result::<T, _>(result, nan_constructor),
+                None => {
+                    on_range_check_error!(T)?;
+                    Ok(nan_constructor())
+                }
+            }
+        )
+    }
+
+    /// Compares fractions a/b and c/d by cross-multiplication in full precision.
+    /// Returns None if any value is NaN.
+    pub fn cmp_cross(
+        a: &IntegerData,
+        b: &IntegerData,
+        c: &IntegerData,
+        d: &IntegerData
+    ) -> ResultOpt<Ordering> {
+        let (a, b, c, d) = match (&a.value, &b.value, &c.value, &d.value) {
+            (
+                IntegerValue::Value(a), IntegerValue::Value(b),
+                IntegerValue::Value(c), IntegerValue::Value(d)
+            ) => (a, b, c, d),
+            _ => return Ok(None)
+        };
+        if b.is_zero() || d.is_zero() {
+            return err!(ExceptionCode::IntegerOverflow, "division by zero")
+        }
+        let ordering = (a * d).cmp(&(c * b));
+        if b.is_negative() != d.is_negative() {
+            Ok(Some(ordering.reverse()))
+        } else {
+            Ok(Some(ordering))
+        }
+    }
+
+    /// Finds the smallest prime factor of non-negative value by trial division up to a limit.
+    /// Returns None if no factor is found within the limit.
+    pub fn smallest_prime_factor(&self, limit: u64) -> Result<Option<IntegerData>> {
+        self.check_neg()?;
+        process_value(self, |value| {
+            if value <= &Int::one() {
+                return Ok(None)
+            }
+            let mut divisor = 2u64;
+            while divisor <= limit {
+                if Int::from(divisor) * divisor > *value {
+                    // no factor up to square root, so value is prime
+                    break
+                }
+                if (value % divisor).is_zero() {
+                    return Ok(Some(IntegerData::from_u64(divisor)))
+                }
+                divisor += 1;
+            }
+            if value <= &Int::from(limit) && Int::from(divisor) * divisor > *value {
+                return Ok(Some(self.clone()))
+            }
+            Ok(None)
+        })
+    }
+
+    /// Tests if value is prime using Miller-Rabin test with a given number of rounds.
+    pub fn is_probable_prime<T, R>(&self, rounds: u32, rng: &mut R) -> Result<bool>
+    where
+        T: OperationBehavior,
+        R: rand::Rng + ?Sized,
+    {
+        let value = match self.value {
+            IntegerValue::NaN(_) => {
+                on_nan_parameter!(T)?;
+                return Ok(false)
+            }
+            IntegerValue::Value(ref value) => value,
+        };
+        if value.is_negative() {
+            on_range_check_error!(T)?;
+            return Ok(false)
+        }
+        Ok(utils::is_probable_prime(value, rounds, rng))
+    }
+
+    /// Computes greatest common divisor of two values.
+    pub fn gcd<T: OperationBehavior>(&self, other: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            other,
+            num::Integer::gcd,
+            construct_single_nan,
+            process_single_result::<T, _>
+        )
+    }
+
+    /// Folds gcd over a slice of values. Returns zero for empty slice and NaN if any value is NaN.
+    pub fn gcd_all(items: &[IntegerData]) -> Result<IntegerData> {
+        items.iter().try_fold(IntegerData::zero(), |acc, item| acc.gcd::<Quiet>(item))
+    }
+
+    /// Computes floor(log10(|value|)) and checks if |value| fits into max_digits decimal digits.
+    /// Zero is considered as a single digit number with logarithm 0.
+    pub fn log10_floor_and_fits(&self, max_digits: u32) -> Result<(u32, bool)> {
+        process_value(self, |value| {
+            let magnitude = value.magnitude();
+            if magnitude.is_zero() {
+                return Ok((0, max_digits >= 1))
+            }
+            // 2^(bits-1) <= |value|, 1233 / 4096 is slightly less than log10(2)
+            let mut log = (((magnitude.bits() - 1) * 1233) >> 12) as u32;
+            while &num::pow::pow(num::BigUint::from(10u32), log as usize + 1) <= magnitude {
+                log += 1;
+            }
+            Ok((log, log < max_digits))
+        })
+    }
+
+    /// Sums digits of absolute value in a given radix (2..=256).
+    pub fn digit_sum(&self, radix: u32) -> Result<IntegerData> {
+        if !(2..=256).contains(&radix) {
+            return err!(ExceptionCode::RangeCheckError, "radix {} is not in the range 2..=256", radix)
+        }
+        process_value(self, |value| {
+            let sum: u64 = value.magnitude().to_radix_le(radix).into_iter().map(u64::from).sum();
+            Ok(IntegerData::from_u64(sum))
+        })
+    }
+
+    /// Repeats digit_sum() until a single digit in a given radix remains.
+    pub fn digital_root(&self, radix: u32) -> Result<IntegerData> {
+        let mut result = self.digit_sum(radix)?;
+        while !result.fits_in_digit(radix) {
+            result = result.digit_sum(radix)?;
+        }
+        Ok(result)
+    }
+
+    fn fits_in_digit(&self, radix: u32) -> bool {
+        matches!(self.value, IntegerValue::Value(ref value) if value < &Int::from(radix))
+    }
+
+    /// Computes multiplicative inverse of value modulo a given modulus.
+    /// The result is normalized to [0, modulus).
+    pub fn modinv<T: OperationBehavior>(&self, modulus: &IntegerData) -> Result<IntegerData> {
+        binary_op::<T, _, _, _, _, _>(
+            self,
+            modulus,
+            utils::modinv,
+            construct_single_nan,
+            |result, nan_constructor| match result {
+                Some(result) => process_single_result::<T, _>(result, nan_constructor),
+                None => {
+                    on_range_check_error!(T)?;
+                    Ok(nan_constructor())
+                }
+            }
+        )
+    }
+
+    /// Solves a system of congruences x = residues[i] (mod moduli[i])
+    /// for pairwise coprime moduli using Chinese remainder theorem.
+    pub fn crt(residues: &[IntegerData], moduli: &[IntegerData]) -> Result<IntegerData> {
+        if residues.len() != moduli.len() {
+            return err!(
+                ExceptionCode::RangeCheckError,
+                "{} residues do not match {} moduli", residues.len(), moduli.len()
+            )
+        }
+        let mut result = Int::zero();
+        let mut product = Int::one();
+        for (residue, modulus) in residues.iter().zip(moduli) {
+            let (residue, modulus) = match (&residue.value, &modulus.value) {
+                (IntegerValue::Value(residue), IntegerValue::Value(modulus)) => (residue, modulus),
+                _ => return err!(ExceptionCode::IntegerOverflow, "not a number")
+            };
+            if !modulus.is_positive() {
+                return err!(ExceptionCode::RangeCheckError, "modulus {} is not positive", modulus)
+            }
+            if modulus.is_one() {
+                continue
+            }
+            let inverse = match utils::modinv(&product, modulus) {
+                Some(inverse) => inverse,
+                None => return err!(
+                    ExceptionCode::RangeCheckError,
+                    "modulus {} is not coprime with previous moduli", modulus
+                )
+            };
+            let step = num::Integer::mod_floor(&((residue - &result) * inverse), modulus);
+            result += &product * step;
+            product *= modulus;
+        }
+        IntegerData::from(result)
+    }
+}
+
+/// Accumulates sum of values in full precision with a single overflow check at the end,
+/// so intermediate sums may exceed 257 bits.
+#[derive(Clone, Debug, Default)]
+pub struct IntegerAccumulator {
+    sum: Int,
+    nan: bool,
+}
+
+impl IntegerAccumulator {
+    pub fn new() -> IntegerAccumulator {
+        Self::default()
+    }
+
+    /// Adds value to the sum. NaN makes the whole sum NaN.
+    pub fn add(&mut self, value: &IntegerData) {
+        match value.value {
+            IntegerValue::NaN(_) => self.nan = true,
+            IntegerValue::Value(ref value) => self.sum += value,
+        }
+    }
+
+    /// Returns accumulated sum taking in account behavior of operation.
+    pub fn finish<T: OperationBehavior>(self) -> Result<IntegerData> {
+        if self.nan {
+            on_nan_parameter!(T)?;
+            return Ok(construct_single_nan())
+        }
+        process_single_result::<T, _>(self.sum, construct_single_nan)
+    }
+}
+
+/// Montgomery form arithmetic modulo a fixed odd modulus N with R = 2^bits(N).
+/// Speeds up series of modular multiplications, replacing division by N with shifts.
+#[derive(Clone, Debug)]
+pub struct Montgomery {
+    modulus: Int,
+    r_bits: usize,
+    r_mask: Int,
+    // -N^(-1) mod R
+    n_prime: Int,
+    // N * R, upper bound of reduction input
+    limit: Int,
+}
+
+impl Montgomery {
+    /// Prepares Montgomery arithmetic for a given odd modulus greater than 1.
+    pub fn new(modulus: &IntegerData) -> Result<Montgomery> {
+        let modulus = modulus.take_value_of(|x| Some(x.clone()))?;
+        if modulus <= Int::one() || num::Integer::is_even(&modulus) {
+            return err!(ExceptionCode::RangeCheckError, "{} is not an odd modulus greater than 1", modulus)
+        }
+        let r_bits = modulus.bits() as usize;
+        let r = Int::one() << r_bits;
+        let inverse = utils::modinv(&modulus, &r).expect("odd modulus is always invertible");
+        Ok(Montgomery {
+            n_prime: &r - inverse,
+            limit: &modulus * &r,
+            r_mask: r - 1,
+            r_bits,
+            modulus,
+        })
+    }
+
+    /// Converts value into Montgomery form: value * R mod N.
+    pub fn to_montgomery(&self, value: &IntegerData) -> Result<IntegerData> {
+        process_value(value, |value| {
+            IntegerData::from(num::Integer::mod_floor(&(value << self.r_bits), &self.modulus))
+        })
+    }
+
+    /// Multiplies two values in Montgomery form.
+    pub fn mul(&self, a: &IntegerData, b: &IntegerData) -> Result<IntegerData> {
+        process_value(a, |a| process_value(b, |b| {
+            let mut t = a * b;
+            if t.is_negative() || t >= self.limit {
+                t = num::Integer::mod_floor(&t, &self.modulus);
+            }
+            IntegerData::from(self.reduce(t))
+        }))
+    }
+
+    /// Converts value from Montgomery form back: value * R^(-1) mod N.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_montgomery(&self, value: &IntegerData) -> Result<IntegerData> {
+        process_value(value, |value| {
+            IntegerData::from(self.reduce(num::Integer::mod_floor(value, &self.modulus)))
+        })
+    }
+
+    /// Montgomery reduction: computes t * R^(-1) mod N for 0 <= t < N * R.
+    fn reduce(&self, t: Int) -> Int {
+        let m = ((&t & &self.r_mask) * &self.n_prime) & &self.r_mask;
+        let mut result = (t + m * &self.modulus) >> self.r_bits;
+        if result >= self.modulus {
+            result -= &self.modulus;
+        }
+        result
+    }
+}
+
+/// Wraps result of division-like operation, where None means division by zero.
+#[inline]
+fn process_division_result<T, FNaN>(result: Option<Int>, nan_constructor: FNaN) -> Result<IntegerData>
+where
+    T: OperationBehavior,
+    FNaN: Fn() -> IntegerData,
+{
+    match result {
+        Some(result) => process_single_result::<T, _>(result, nan_constructor),
+        None => {
+            on_integer_overflow!(T)?;
+            Ok(nan_constructor().with_nan_reason("division by zero"))
+        }
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl IntegerData {
+    /// Generates uniformly distributed value in [lo, hi] for property tests.
+    /// Panics if any bound is NaN or lo > hi.
+    pub fn random_in_range<R: rand::Rng + ?Sized>(
+        rng: &mut R,
+        lo: &IntegerData,
+        hi: &IntegerData
+    ) -> IntegerData {
+        let (lo, hi) = match (&lo.value, &hi.value) {
+            (IntegerValue::Value(lo), IntegerValue::Value(hi)) if lo <= hi => (lo, hi),
+            _ => panic!("invalid range {}..={}", lo, hi)
+        };
+        let range = (hi - lo + 1).magnitude().clone();
+        let bits = range.bits() as usize;
+        let mut bytes = vec![0u8; bits.div_ceil(8)];
+        loop {
+            rand::RngCore::fill_bytes(rng, &mut bytes);
+            bytes[0] &= 0xFFu8 >> (bytes.len() * 8 - bits);
+            let candidate = num::BigUint::from_bytes_be(&bytes);
+            if candidate < range {
+                return IntegerData::from(lo + Int::from(candidate)).expect("Should always fit")
+            }
+        }
+    }
+}
+
+pub mod utils {
+
+    use crate::stack::integer::{Int, math::Round, utils::check_overflow};
+    use num::ToPrimitive;
+    use num_traits::{One, Signed, Zero};
+    use std::cmp::Ordering;
+
+    #[inline]
+    pub fn divmod(dividend: &Int, divisor: &Int, rounding: Round) -> (Int, Int) {
+        match rounding {
+            Round::FloorToNegativeInfinity =>
+                num::Integer::div_mod_floor(dividend, divisor),
+            Round::FloorToZero =>
+                num::Integer::div_rem(dividend, divisor),
+            Round::Ceil => {
+                let (mut quotient, mut remainder) = num::Integer::div_rem(dividend, divisor);
+                round_ceil(&mut quotient, &mut remainder, dividend, divisor);
+                (quotient, remainder)
+            }
+            Round::Nearest => {
+                let (mut quotient, mut remainder) = num::Integer::div_rem(dividend, divisor);
+                round_nearest(&mut quotient, &mut remainder, dividend, divisor);
+                (quotient, remainder)
+            }
+        }
+    }
+
+    /// Calculates the multiplicative inverse using extended Euclidean algorithm.
+    /// Returns None if modulus <= 1 or the inverse doesn't exist.
+    pub fn modinv(value: &Int, modulus: &Int) -> Option<Int> {
+        if modulus <= &Int::one() {
+            return None
+        }
+        let (mut r0, mut r1) = (modulus.clone(), num::Integer::mod_floor(value, modulus));
+        let (mut t0, mut t1) = (Int::zero(), Int::one());
+        while !r1.is_zero() {
+            let q = &r0 / &r1;
+            let r2 = &r0 - &q * &r1;
+            r0 = std::mem::replace(&mut r1, r2);
+            let t2 = &t0 - &q * &t1;
+            t0 = std::mem::replace(&mut t1, t2);
+        }
+        if !r0.is_one() {
+            return None
+        }
+        Some(num::Integer::mod_floor(&t0, modulus))
+    }
+
+    /// Calculates binomial coefficient using multiplicative formula.
+    /// Returns None if k < 0 or k > n. Stops as soon as intermediate result overflows.
+    pub fn binomial(n: &Int, k: &Int) -> Option<Int> {
+        if k.is_negative() || k > n {
+            return None
+        }
+        let n_minus_k = n - k;
+        let k = std::cmp::min(k, &n_minus_k);
+        let base = n - k;
+        let mut result = Int::one();
+        let mut i = Int::one();
+        while &i <= k {
+            result = result * (&base + &i) / &i;
+            if !check_overflow(&result) {
+                break
+            }
+            i += 1;
+        }
+        Some(result)
+    }
+
+    /// Raises base to non-negative exponent. Returns None if result surely overflows:
+    /// |base| >= 2^(bits - 1), so the result takes at least (bits - 1) * exp bits.
+    pub fn checked_pow(base: &Int, exp: &Int) -> Option<Int> {
+        if base.magnitude().bits() <= 1 {
+            // 0, 1 and -1 may be raised to any power
+            if base.is_zero() {
+                return Some(if exp.is_zero() { Int::one() } else { Int::zero() })
+            }
+            if base.is_negative() && num::Integer::is_odd(exp) {
+                return Some(-Int::one())
+            }
+            return Some(Int::one())
+        }
+        let exp = exp.to_u32().filter(|exp| (base.bits() - 1) * *exp as u64 <= 256)?;
+        Some(num::pow::pow(base.clone(), exp as usize))
+    }
+
+    /// Raises base to non-negative exponent modulo 2^n using fixed 4-bit window.
+    pub fn pow_mod_pow2(base: &Int, exp: &Int, n: usize) -> Int {
+        let mask = (Int::one() << n) - 1;
+        let mut table = Vec::with_capacity(16);
+        table.push(Int::one() & &mask);
+        for i in 1..16 {
+            let next = (&table[i - 1] * base) & &mask;
+            table.push(next);
+        }
+        let mut result = table[0].clone();
+        for byte in exp.magnitude().to_bytes_be() {
+            for window in [byte >> 4, byte & 0xF] {
+                for _ in 0..4 {
+                    result = (&result * &result) & &mask;
+                }
+                result = (result * &table[window as usize]) & &mask;
+            }
+        }
+        result
+    }
+
+    /// Miller-Rabin primality test for non-negative value.
+    pub fn is_probable_prime<R: rand::Rng + ?Sized>(value: &Int, rounds: u32, rng: &mut R) -> bool {
+        const SMALL_PRIMES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        if value <= &Int::one() {
+            return false
+        }
+        for prime in SMALL_PRIMES {
+            if value == &Int::from(prime) {
+                return true
+            }
+            if (value % prime).is_zero() {
+                return false
+            }
+        }
+        let value_minus_one = value - 1;
+        let mut d = value_minus_one.clone();
+        let mut s = 0;
+        while num::Integer::is_even(&d) {
+            d >>= 1;
+            s += 1;
+        }
+        // witnesses are taken from [2, value - 2]
+        let range = value - 3;
+        let mut bytes = vec![0u8; (value.bits() as usize).div_ceil(8) + 8];
+        'witness: for _ in 0..rounds {
+            rand::RngCore::fill_bytes(rng, &mut bytes);
+            let witness = Int::from_bytes_be(num::bigint::Sign::Plus, &bytes) % &range + 2;
+            let mut x = witness.modpow(&d, value);
+            if x.is_one() || x == value_minus_one {
+                continue
+            }
+            for _ in 1..s {
+                x = &x * &x % value;
+                if x == value_minus_one {
+                    continue 'witness
+                }
+            }
+            return false
+        }
+        true
+    }
+
+    #[inline]
+    pub fn div_by_shift(dividend: &Int, shift: usize, rounding: Round) -> (Int, Int) {
+        let divisor = Int::one() << shift;
+        let remainder_mask = divisor.clone() - 1;
+        let (mut quotient, mut remainder) = if dividend.sign() == num::bigint::Sign::Minus {
+            let dividend = dividend.abs();
+            (-(dividend.clone() >> shift), -(dividend & &remainder_mask))
+        } else {
+            (dividend >> shift, dividend & &remainder_mask)
+        };
+        match rounding {
+            Round::FloorToNegativeInfinity =>
+                round_floor_to_negative_infinity(
+                    &mut quotient, &mut remainder, dividend, &divisor
+                ),
+            Round::Ceil =>
+                round_ceil(&mut quotient, &mut remainder, dividend, &divisor),
+            Round::Nearest =>
+                round_nearest(&mut quotient, &mut remainder, dividend, &divisor),
+            _ => {}
+        }
+        (quotient, remainder)
+    }
+
+    #[inline]
+    fn round_floor_to_negative_infinity(
+        quotient: &mut Int,
+        remainder: &mut Int,
+        dividend: &Int,
+        divisor: &Int,
+    ) {
+        if remainder.is_zero() || remainder.sign() == divisor.sign() {
+            // No rounding needed
+            return;
+        }
+        *remainder += divisor;
+        if dividend.sign() == divisor.sign() {
+            *quotient += 1;
+        } else {
+            *quotient -= 1;
+        }
+    }
+
+    #[inline]
+    fn round_ceil(
+        quotient: &mut Int,
+        remainder: &mut Int,
+        dividend: &Int,
+        divisor: &Int,
+    ) {
+        if remainder.is_zero() || remainder.sign() != divisor.sign() {
+            // No rounding needed
+            return;
+        }
+        *remainder -= divisor;
+        if dividend.sign() == divisor.sign() {
+            *quotient += 1;
+        } else {
+            *quotient -= 1;
+        }
+    }
+
+    #[inline]
+    fn round_nearest(
+        quotient: &mut Int,
+        remainder: &mut Int,
+        dividend: &Int,
+        divisor: &Int,
+    ) {
+        if remainder.is_zero() {
+            // No rounding needed
+            return;
+        }
+        //  5 / 2  ->   2,  1  ->   3, -1
+        // -5 / 2  ->  -2, -1  ->  -2, -1
+        //  5 /-2  ->  -2,  1  ->  -2,  1
+        // -5 /-2  ->   2, -1  ->   3,  1
+        let r_x2: Int = remainder.clone() << 1;
+        let cmp_result = r_x2.abs().cmp(&divisor.abs());
+        let is_not_negative = dividend.sign() == divisor.sign();
+        if cmp_result == Ordering::Greater
+            || (cmp_result == Ordering::Equal && is_not_negative)
+        {
+            if divisor.sign() == remainder.sign() {
+                *remainder -= divisor;
+            } else {
+                *remainder += divisor;
+            }
+            if is_not_negative {
+                *quotient += 1;
+            } else {
+                *quotient -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::integer::tests::exception_code;
+
+    #[test]
+    fn test_modinv() {
+        let modulus = IntegerData::from_i32(11);
+        let result = IntegerData::from_i32(3).modinv::<Signaling>(&modulus).unwrap();
+        assert_eq!(result, IntegerData::from_i32(4));
+        let result = IntegerData::from_i32(-3).modinv::<Signaling>(&modulus).unwrap();
+        assert_eq!(result, IntegerData::from_i32(7));
+    }
+
+    #[test]
+    fn test_modinv_not_invertible() {
+        let value = IntegerData::from_i32(4);
+        let modulus = IntegerData::from_i32(8);
+        assert_eq!(exception_code(value.modinv::<Signaling>(&modulus)), Some(ExceptionCode::RangeCheckError));
+        assert!(value.modinv::<Quiet>(&modulus).unwrap().is_nan());
+        assert!(value.modinv::<Quiet>(&IntegerData::one()).unwrap().is_nan());
+        assert_eq!(exception_code(IntegerData::nan().modinv::<Signaling>(&modulus)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_crt() {
+        let residues = [IntegerData::from_i32(2), IntegerData::from_i32(3)];
+        let moduli = [IntegerData::from_i32(3), IntegerData::from_i32(5)];
+        assert_eq!(IntegerData::crt(&residues, &moduli).unwrap(), IntegerData::from_i32(8));
+    }
+
+    #[test]
+    fn test_crt_invalid_moduli() {
+        let residues = [IntegerData::from_i32(1), IntegerData::from_i32(1)];
+        let moduli = [IntegerData::from_i32(4), IntegerData::from_i32(6)];
+        assert_eq!(exception_code(IntegerData::crt(&residues, &moduli)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::crt(&residues, &moduli[..1])), Some(ExceptionCode::RangeCheckError));
+    }
+
+    fn max_value() -> IntegerData {
+        IntegerData::from((Int::one() << 256) - 1).unwrap()
+    }
+
+    fn min_value() -> IntegerData {
+        IntegerData::from(-(Int::one() << 256)).unwrap()
+    }
+
+    #[test]
+    fn test_midpoint() {
+        let midpoint = |a: i32, b: i32| IntegerData::from_i32(a)
+            .midpoint::<Signaling>(&IntegerData::from_i32(b)).unwrap();
+        assert_eq!(midpoint(3, 4), IntegerData::from_i32(3));
+        assert_eq!(midpoint(-3, -4), IntegerData::from_i32(-4));
+        assert_eq!(midpoint(-10, 10), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_midpoint_of_extreme_values() {
+        // sums of these values don't fit into 257 bits
+        let max = max_value();
+        let min = min_value();
+        assert!(max.add::<Signaling>(&max).is_err());
+        assert_eq!(max.midpoint::<Signaling>(&max).unwrap(), max);
+        assert_eq!(min.midpoint::<Signaling>(&min).unwrap(), min);
+        assert_eq!(max.midpoint::<Signaling>(&max.sub_i8::<Signaling>(&2).unwrap()).unwrap(),
+            max.sub_i8::<Signaling>(&1).unwrap());
+        assert!(max.midpoint::<Quiet>(&IntegerData::nan()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_div_exact() {
+        let ten = IntegerData::from_i32(10);
+        assert_eq!(ten.div_exact::<Signaling>(&IntegerData::from_i32(5)).unwrap(), (IntegerData::from_i32(2), true));
+        assert_eq!(ten.div_exact::<Signaling>(&IntegerData::from_i32(3)).unwrap(), (IntegerData::from_i32(3), false));
+        assert_eq!(
+            IntegerData::from_i32(-10).div_exact::<Signaling>(&IntegerData::from_i32(3)).unwrap(),
+            (IntegerData::from_i32(-4), false)
+        );
+    }
+
+    #[test]
+    fn test_div_exact_by_zero() {
+        let ten = IntegerData::from_i32(10);
+        assert_eq!(exception_code(ten.div_exact::<Signaling>(&IntegerData::zero())), Some(ExceptionCode::IntegerOverflow));
+        let (quotient, exact) = ten.div_exact::<Quiet>(&IntegerData::zero()).unwrap();
+        assert!(quotient.is_nan());
+        assert!(!exact);
+    }
+
+    #[test]
+    fn test_is_divisible_by() {
+        let is_divisible_by = |a: i32, b: i32| IntegerData::from_i32(a)
+            .is_divisible_by::<Signaling>(&IntegerData::from_i32(b)).unwrap();
+        assert!(is_divisible_by(12, 4));
+        assert!(!is_divisible_by(12, 5));
+        assert!(is_divisible_by(-12, 4));
+        assert!(is_divisible_by(-12, -3));
+        assert!(!is_divisible_by(12, -5));
+        assert!(is_divisible_by(0, 7));
+    }
+
+    #[test]
+    fn test_is_divisible_by_zero() {
+        let value = IntegerData::from_i32(12);
+        assert_eq!(exception_code(value.is_divisible_by::<Signaling>(&IntegerData::zero())), Some(ExceptionCode::IntegerOverflow));
+        assert!(!value.is_divisible_by::<Quiet>(&IntegerData::zero()).unwrap());
+        assert_eq!(exception_code(value.is_divisible_by::<Signaling>(&IntegerData::nan())), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_to_ratio() {
+        assert_eq!(IntegerData::from_i32(3).to_ratio(1).unwrap(), (IntegerData::from_i32(3), IntegerData::from_i32(2)));
+        assert_eq!(IntegerData::from_i32(4).to_ratio(2).unwrap(), (IntegerData::one(), IntegerData::one()));
+        assert_eq!(IntegerData::from_i32(-6).to_ratio(3).unwrap(), (IntegerData::from_i32(-3), IntegerData::from_i32(4)));
+        assert_eq!(exception_code(IntegerData::nan().to_ratio(1)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(IntegerData::from_i32(2).to_ratio(256).unwrap(), (IntegerData::one(), IntegerData::from(Int::one() << 255).unwrap()));
+        assert_eq!(exception_code(IntegerData::one().to_ratio(257)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::one().to_ratio(usize::MAX)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_apply_unary() {
+        let apply = |op: UnaryOp, value: i32| IntegerData::apply_unary::<Signaling>(op, &IntegerData::from_i32(value)).unwrap();
+        assert_eq!(apply(UnaryOp::Neg, 5), IntegerData::from_i32(-5));
+        assert_eq!(apply(UnaryOp::Abs, -5), IntegerData::from_i32(5));
+        assert_eq!(apply(UnaryOp::Not, 5), IntegerData::from_i32(-6));
+        assert_eq!(apply(UnaryOp::Inc, 5), IntegerData::from_i32(6));
+        assert_eq!(apply(UnaryOp::Dec, 5), IntegerData::from_i32(4));
+        assert_eq!(apply(UnaryOp::Signum, -5), IntegerData::minus_one());
+        assert_eq!(apply(UnaryOp::Signum, 0), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_apply_unary_overflow() {
+        assert_eq!(
+            exception_code(IntegerData::apply_unary::<Signaling>(UnaryOp::Inc, &max_value())),
+            Some(ExceptionCode::IntegerOverflow)
+        );
+        assert!(IntegerData::apply_unary::<Quiet>(UnaryOp::Neg, &min_value()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_reduce_mod_pow2_signed() {
+        let reduce = |value: i32, n: usize| IntegerData::from_i32(value).reduce_mod_pow2_signed(n).unwrap();
+        assert_eq!(reduce(200, 8), IntegerData::from_i32(-56));
+        assert_eq!(reduce(127, 8), IntegerData::from_i32(127));
+        assert_eq!(reduce(128, 8), IntegerData::from_i32(-128));
+        assert_eq!(reduce(-129, 8), IntegerData::from_i32(127));
+        assert_eq!(reduce(5, 0), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_reduce_mod_pow2_signed_wide() {
+        assert_eq!(max_value().reduce_mod_pow2_signed(usize::MAX).unwrap(), max_value());
+        assert_eq!(min_value().reduce_mod_pow2_signed(usize::MAX).unwrap(), min_value());
+        assert_eq!(max_value().reduce_mod_pow2_signed(256).unwrap(), IntegerData::minus_one());
+    }
+
+    #[test]
+    fn test_add_dyn_matches_static_behavior() {
+        let (a, b) = (IntegerData::from_i32(7), IntegerData::from_i32(-12));
+        assert_eq!(a.add_dyn(&b, BehaviorKind::Quiet).unwrap(), a.add::<Quiet>(&b).unwrap());
+        assert_eq!(a.add_dyn(&b, BehaviorKind::Signaling).unwrap(), a.add::<Signaling>(&b).unwrap());
+
+        let (max, one) = (max_value(), IntegerData::one());
+        assert!(max.add_dyn(&one, BehaviorKind::Quiet).unwrap().is_nan());
+        assert!(max.add::<Quiet>(&one).unwrap().is_nan());
+        assert_eq!(exception_code(max.add_dyn(&one, BehaviorKind::Signaling)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(max.add::<Signaling>(&one)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_ceil_div_pow2() {
+        assert_eq!(IntegerData::from_i32(1025).ceil_div_pow2(10).unwrap(), IntegerData::from_i32(2));
+        assert_eq!(IntegerData::from_i32(1024).ceil_div_pow2(10).unwrap(), IntegerData::one());
+        assert_eq!(IntegerData::zero().ceil_div_pow2(10).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::from_i32(7).ceil_div_pow2(0).unwrap(), IntegerData::from_i32(7));
+        assert_eq!(max_value().ceil_div_pow2(256).unwrap(), IntegerData::one());
+        assert_eq!(IntegerData::from_i32(7).ceil_div_pow2(usize::MAX).unwrap(), IntegerData::one());
+        assert_eq!(IntegerData::zero().ceil_div_pow2(usize::MAX).unwrap(), IntegerData::zero());
+        assert_eq!(exception_code(IntegerData::minus_one().ceil_div_pow2(1)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::nan().ceil_div_pow2(1)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_saturate_signed() {
+        assert_eq!(IntegerData::from_i32(300).saturate_signed(8).unwrap(), IntegerData::from_i32(127));
+        assert_eq!(IntegerData::from_i32(-300).saturate_signed(8).unwrap(), IntegerData::from_i32(-128));
+        assert_eq!(IntegerData::from_i32(-5).saturate_signed(8).unwrap(), IntegerData::from_i32(-5));
+        assert!(IntegerData::nan().saturate_signed(8).unwrap().is_nan());
+        assert_eq!(exception_code(IntegerData::one().saturate_signed(0)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(max_value().saturate_signed(usize::MAX).unwrap(), max_value());
+        assert_eq!(min_value().saturate_signed(usize::MAX).unwrap(), min_value());
+        assert_eq!(max_value().saturate_signed(257).unwrap(), max_value());
+        assert_eq!(max_value().saturate_signed(256).unwrap(), IntegerData::from((Int::one() << 255) - 1).unwrap());
+    }
+
+    #[test]
+    fn test_factorial() {
+        assert_eq!(IntegerData::factorial::<Signaling>(0).unwrap(), IntegerData::one());
+        assert_eq!(IntegerData::factorial::<Signaling>(1).unwrap(), IntegerData::one());
+        assert_eq!(IntegerData::factorial::<Signaling>(5).unwrap(), IntegerData::from_i32(120));
+    }
+
+    #[test]
+    fn test_factorial_overflow_boundary() {
+        // 57! takes 255 bits, 58! takes 261 bits
+        let expected = (2..=57u32).fold(Int::one(), |acc, i| acc * i);
+        assert_eq!(IntegerData::factorial::<Signaling>(57).unwrap(), IntegerData::from(expected).unwrap());
+        assert_eq!(exception_code(IntegerData::factorial::<Signaling>(58)), Some(ExceptionCode::IntegerOverflow));
+        assert!(IntegerData::factorial::<Quiet>(58).unwrap().is_nan());
+        assert!(IntegerData::factorial::<Quiet>(u32::MAX).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_binomial() {
+        let binomial = |n: i32, k: i32| IntegerData::binomial::<Signaling>(
+            &IntegerData::from_i32(n), &IntegerData::from_i32(k)
+        ).unwrap();
+        assert_eq!(binomial(5, 2), IntegerData::from_i32(10));
+        assert_eq!(binomial(10, 0), IntegerData::one());
+        assert_eq!(binomial(10, 10), IntegerData::one());
+        assert_eq!(binomial(10, 8), IntegerData::from_i32(45));
+        assert_eq!(binomial(0, 0), IntegerData::one());
+    }
+
+    #[test]
+    fn test_binomial_errors() {
+        let (n, k) = (IntegerData::from_i32(5), IntegerData::from_i32(6));
+        assert_eq!(exception_code(IntegerData::binomial::<Signaling>(&n, &k)), Some(ExceptionCode::RangeCheckError));
+        assert!(IntegerData::binomial::<Quiet>(&n, &k).unwrap().is_nan());
+        let k = IntegerData::minus_one();
+        assert_eq!(exception_code(IntegerData::binomial::<Signaling>(&n, &k)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(
+            exception_code(IntegerData::binomial::<Signaling>(&n, &IntegerData::nan())),
+            Some(ExceptionCode::IntegerOverflow)
+        );
+        // C(300, 150) takes about 296 bits
+        let (n, k) = (IntegerData::from_i32(300), IntegerData::from_i32(150));
+        assert_eq!(exception_code(IntegerData::binomial::<Signaling>(&n, &k)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_random_in_range() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let (lo, hi) = (IntegerData::from_i32(-5), IntegerData::from_i32(5));
+        let (mut hit_lo, mut hit_hi) = (false, false);
+        for _ in 0..1000 {
+            let value = IntegerData::random_in_range(&mut rng, &lo, &hi);
+            assert!(value.in_range::<Signaling>(&lo, &hi).unwrap());
+            hit_lo |= value == lo;
+            hit_hi |= value == hi;
+        }
+        assert!(hit_lo && hit_hi);
+        let (lo, hi) = (min_value(), max_value());
+        for _ in 0..100 {
+            let value = IntegerData::random_in_range(&mut rng, &lo, &hi);
+            assert!(value.in_range::<Signaling>(&lo, &hi).unwrap());
+        }
+        let single = IntegerData::from_i32(7);
+        assert_eq!(IntegerData::random_in_range(&mut rng, &single, &single), single);
+    }
+
+    #[test]
+    fn test_rem_euclid() {
+        let rem = |x: i32, y: i32| IntegerData::from_i32(x).rem_euclid::<Signaling>(&IntegerData::from_i32(y)).unwrap();
+        assert_eq!(rem(-7, 3), IntegerData::from_i32(2));
+        assert_eq!(rem(7, -3), IntegerData::from_i32(1));
+        assert_eq!(rem(-7, -3), IntegerData::from_i32(2));
+        assert_eq!(rem(7, 3), IntegerData::from_i32(1));
+        assert_eq!(rem(-6, 3), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_rem_euclid_by_zero() {
+        let value = IntegerData::from_i32(7);
+        assert_eq!(exception_code(value.rem_euclid::<Signaling>(&IntegerData::zero())), Some(ExceptionCode::IntegerOverflow));
+        assert!(value.rem_euclid::<Quiet>(&IntegerData::zero()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_div_euclid_identity() {
+        for x in -7..=7 {
+            for d in [-3, -2, -1, 1, 2, 3] {
+                let (value, divisor) = (IntegerData::from_i32(x), IntegerData::from_i32(d));
+                let quotient = value.div_euclid::<Signaling>(&divisor).unwrap();
+                let remainder = value.rem_euclid::<Signaling>(&divisor).unwrap();
+                let restored = quotient.mul::<Signaling>(&divisor).unwrap().add::<Signaling>(&remainder).unwrap();
+                assert_eq!(restored, value, "{} div_euclid {}", x, d);
+                assert!(remainder.in_range::<Signaling>(&IntegerData::zero(), &IntegerData::from_i32(d.abs() - 1)).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_euclid_by_zero() {
+        let value = IntegerData::from_i32(7);
+        assert_eq!(exception_code(value.div_euclid::<Signaling>(&IntegerData::zero())), Some(ExceptionCode::IntegerOverflow));
+        assert!(value.div_euclid::<Quiet>(&IntegerData::zero()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_cmp_cross() {
+        let cmp = |a: i32, b: i32, c: i32, d: i32| IntegerData::cmp_cross(
+            &IntegerData::from_i32(a), &IntegerData::from_i32(b),
+            &IntegerData::from_i32(c), &IntegerData::from_i32(d)
+        ).unwrap();
+        assert_eq!(cmp(1, 3, 2, 5), Some(Ordering::Less));
+        assert_eq!(cmp(2, 5, 1, 3), Some(Ordering::Greater));
+        assert_eq!(cmp(1, -3, 2, 5), Some(Ordering::Less));
+        assert_eq!(cmp(-1, -3, 2, 5), Some(Ordering::Less));
+        assert_eq!(cmp(2, 4, -1, -2), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_cmp_cross_errors() {
+        let (one, nan) = (IntegerData::one(), IntegerData::nan());
+        assert_eq!(IntegerData::cmp_cross(&nan, &one, &one, &one).unwrap(), None);
+        let result = IntegerData::cmp_cross(&one, &IntegerData::zero(), &one, &one);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_abs_diff() {
+        let diff = |x: &IntegerData, y: &IntegerData| x.abs_diff::<Signaling>(y).unwrap();
+        assert_eq!(diff(&IntegerData::from_i32(3), &IntegerData::from_i32(-4)), IntegerData::from_i32(7));
+        assert_eq!(diff(&IntegerData::from_i32(-4), &IntegerData::from_i32(3)), IntegerData::from_i32(7));
+        assert_eq!(diff(&max_value(), &IntegerData::zero()), max_value());
+    }
+
+    #[test]
+    fn test_abs_diff_overflow() {
+        assert_eq!(exception_code(max_value().abs_diff::<Signaling>(&min_value())), Some(ExceptionCode::IntegerOverflow));
+        assert!(max_value().abs_diff::<Quiet>(&min_value()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_with_sign() {
+        let with_sign = |x: i32, negative: bool| IntegerData::from_i32(x).with_sign::<Signaling>(negative).unwrap();
+        assert_eq!(with_sign(5, true), IntegerData::from_i32(-5));
+        assert_eq!(with_sign(-5, true), IntegerData::from_i32(-5));
+        assert_eq!(with_sign(-5, false), IntegerData::from_i32(5));
+        assert_eq!(with_sign(0, true), IntegerData::zero());
+        assert_eq!(exception_code(min_value().with_sign::<Signaling>(false)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(min_value().with_sign::<Signaling>(true).unwrap(), min_value());
+    }
+
+    #[test]
+    fn test_smallest_prime_factor() {
+        let factor = |x: u32, limit: u64| IntegerData::from_u32(x).smallest_prime_factor(limit).unwrap();
+        assert_eq!(factor(15, 100), Some(IntegerData::from_u32(3)));
+        assert_eq!(factor(13, 100), Some(IntegerData::from_u32(13)));
+        assert_eq!(factor(13, 2), None);
+        assert_eq!(factor(49, 10), Some(IntegerData::from_u32(7)));
+        assert_eq!(factor(101, 5), None);
+        assert_eq!(factor(1, 100), None);
+        assert_eq!(factor(0, 100), None);
+        assert_eq!(exception_code(IntegerData::from_i32(-3).smallest_prime_factor(100)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut is_prime = |x: Int| IntegerData::from(x).unwrap().is_probable_prime::<Signaling, _>(20, &mut rng).unwrap();
+        for prime in [2, 3, 37, 97, 7919] {
+            assert!(is_prime(Int::from(prime)), "{}", prime);
+        }
+        assert!(is_prime((Int::one() << 61) - 1));
+        assert!(is_prime((Int::one() << 127) - 1));
+        for composite in [0, 1, 4, 91, 252601] {
+            assert!(!is_prime(Int::from(composite)), "{}", composite);
+        }
+        assert!(!is_prime(((Int::one() << 61) - 1) * ((Int::one() << 31) - 1)));
+    }
+
+    #[test]
+    fn test_is_probable_prime_errors() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let negative = IntegerData::from_i32(-7);
+        assert_eq!(exception_code(negative.is_probable_prime::<Signaling, _>(20, &mut rng)), Some(ExceptionCode::RangeCheckError));
+        assert!(!negative.is_probable_prime::<Quiet, _>(20, &mut rng).unwrap());
+        let nan = IntegerData::nan();
+        assert_eq!(exception_code(nan.is_probable_prime::<Signaling, _>(20, &mut rng)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_checked_pow_big() {
+        let pow = |base: i32, exp: &IntegerData| IntegerData::from_i32(base).checked_pow_big::<Signaling>(exp);
+        assert_eq!(pow(3, &IntegerData::from_u32(100)).unwrap(), IntegerData::from(num::pow::pow(Int::from(3), 100)).unwrap());
+        assert_eq!(pow(-2, &IntegerData::from_u32(255)).unwrap(), IntegerData::from(-(Int::one() << 255)).unwrap());
+        assert_eq!(pow(7, &IntegerData::zero()).unwrap(), IntegerData::one());
+        let huge = IntegerData::from(Int::one() << 200).unwrap();
+        assert_eq!(pow(1, &huge).unwrap(), IntegerData::one());
+        assert_eq!(pow(0, &huge).unwrap(), IntegerData::zero());
+        let huge_odd = IntegerData::from((Int::one() << 200) + 1).unwrap();
+        assert_eq!(pow(-1, &huge_odd).unwrap(), IntegerData::minus_one());
+        assert_eq!(pow(-1, &huge).unwrap(), IntegerData::one());
+    }
+
+    #[test]
+    fn test_checked_pow_big_errors() {
+        let pow = |base: i32, exp: &IntegerData| IntegerData::from_i32(base).checked_pow_big::<Signaling>(exp);
+        assert_eq!(exception_code(pow(2, &IntegerData::from_u32(256))), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(pow(2, &IntegerData::from_u32(300))), Some(ExceptionCode::IntegerOverflow));
+        let huge = IntegerData::from(Int::one() << 200).unwrap();
+        assert_eq!(exception_code(pow(2, &huge)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(pow(2, &IntegerData::minus_one())), Some(ExceptionCode::RangeCheckError));
+        assert!(IntegerData::from_i32(2).checked_pow_big::<Quiet>(&huge).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_saturate_unsigned() {
+        let saturate = |x: i32, bits: usize| IntegerData::from_i32(x).saturate_unsigned(bits).unwrap();
+        assert_eq!(saturate(-5, 8), IntegerData::zero());
+        assert_eq!(saturate(300, 8), IntegerData::from_u32(255));
+        assert_eq!(saturate(200, 8), IntegerData::from_u32(200));
+        assert_eq!(saturate(5, 0), IntegerData::zero());
+        assert!(IntegerData::nan().saturate_unsigned(8).unwrap().is_nan());
+        assert_eq!(max_value().saturate_unsigned(1000).unwrap(), max_value());
+        assert_eq!(max_value().saturate_unsigned(usize::MAX).unwrap(), max_value());
+        assert_eq!(min_value().saturate_unsigned(usize::MAX).unwrap(), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_gcd_all() {
+        let values = |items: &[i32]| items.iter().map(|x| IntegerData::from_i32(*x)).collect::<Vec<_>>();
+        assert_eq!(IntegerData::gcd_all(&values(&[12, 18, 24])).unwrap(), IntegerData::from_i32(6));
+        assert_eq!(IntegerData::gcd_all(&values(&[-12, 18])).unwrap(), IntegerData::from_i32(6));
+        assert_eq!(IntegerData::gcd_all(&values(&[7])).unwrap(), IntegerData::from_i32(7));
+        assert_eq!(IntegerData::gcd_all(&[]).unwrap(), IntegerData::zero());
+        assert!(IntegerData::gcd_all(&[IntegerData::nan(), IntegerData::from_i32(4)]).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_would_mul_overflow_boundary() {
+        let pow2 = |n: usize| IntegerData::from(Int::one() << n).unwrap();
+        let cases = [
+            (pow2(128), pow2(128), true),
+            (pow2(128), IntegerData::from((Int::one() << 128) - 1).unwrap(), false),
+            (pow2(128).neg::<Signaling>().unwrap(), pow2(128), false),
+            (pow2(255), IntegerData::from_i32(2), true),
+            (max_value(), IntegerData::one(), false),
+            (min_value(), IntegerData::one(), false),
+            (min_value(), IntegerData::minus_one(), true),
+            (pow2(200), pow2(100), true),
+            (pow2(200), IntegerData::zero(), false),
+        ];
+        for (x, y, overflow) in cases {
+            assert_eq!(x.would_mul_overflow::<Signaling>(&y).unwrap(), overflow, "{} * {}", x, y);
+            assert_eq!(x.mul::<Signaling>(&y).is_err(), overflow, "{} * {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_would_mul_overflow_nan() {
+        let nan = IntegerData::nan();
+        assert_eq!(exception_code(nan.would_mul_overflow::<Signaling>(&IntegerData::one())), Some(ExceptionCode::IntegerOverflow));
+        assert!(!nan.would_mul_overflow::<Quiet>(&IntegerData::one()).unwrap());
+    }
+
+    #[test]
+    fn test_mod_mersenne_matches_division() {
+        let values = [
+            Int::zero(), Int::one(), Int::from(2), Int::from(12345), Int::from(u64::MAX),
+            (Int::one() << 127) - 1, Int::one() << 127, (Int::one() << 256) - 1,
+        ];
+        for p_bits in [1, 2, 5, 13, 31, 61, 127] {
+            let modulus = IntegerData::from((Int::one() << p_bits) - 1).unwrap();
+            for value in values.iter() {
+                let value = IntegerData::from(value.clone()).unwrap();
+                let expected = value.div::<Signaling>(&modulus, Round::FloorToNegativeInfinity).unwrap().1;
+                assert_eq!(value.mod_mersenne(p_bits).unwrap(), expected, "{} mod 2^{} - 1", value, p_bits);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_mersenne_errors() {
+        assert_eq!(exception_code(IntegerData::minus_one().mod_mersenne(13)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::one().mod_mersenne(0)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::nan().mod_mersenne(13)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_mod_mersenne_wide_modulus() {
+        assert_eq!(max_value().mod_mersenne(257).unwrap(), max_value());
+        assert_eq!(max_value().mod_mersenne(usize::MAX).unwrap(), max_value());
+        assert_eq!(max_value().mod_mersenne(256).unwrap(), IntegerData::zero());
+    }
+
+    fn montgomery_moduli() -> Vec<Int> {
+        vec![Int::from(1_000_000_007u64), (Int::one() << 255) - 19]
+    }
+
+    #[test]
+    fn test_montgomery_round_trip() {
+        for modulus in montgomery_moduli() {
+            let montgomery = Montgomery::new(&IntegerData::from(modulus.clone()).unwrap()).unwrap();
+            for value in [Int::zero(), Int::one(), Int::from(-5), Int::from(123456789), &modulus - 1, &modulus + 3] {
+                let form = montgomery.to_montgomery(&IntegerData::from(value.clone()).unwrap()).unwrap();
+                let restored = montgomery.from_montgomery(&form).unwrap();
+                let expected = IntegerData::from(num::Integer::mod_floor(&value, &modulus)).unwrap();
+                assert_eq!(restored, expected, "{} mod {}", value, modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_mul() {
+        for modulus in montgomery_moduli() {
+            let montgomery = Montgomery::new(&IntegerData::from(modulus.clone()).unwrap()).unwrap();
+            let (a, b) = (&modulus - 2, Int::from(987654321));
+            let product = montgomery.mul(
+                &montgomery.to_montgomery(&IntegerData::from(a.clone()).unwrap()).unwrap(),
+                &montgomery.to_montgomery(&IntegerData::from(b.clone()).unwrap()).unwrap()
+            ).unwrap();
+            let expected = IntegerData::from(&a * &b % &modulus).unwrap();
+            assert_eq!(montgomery.from_montgomery(&product).unwrap(), expected);
+            // a^e by repeated multiplication in Montgomery form
+            let base = montgomery.to_montgomery(&IntegerData::from(a.clone()).unwrap()).unwrap();
+            let mut power = montgomery.to_montgomery(&IntegerData::one()).unwrap();
+            for _ in 0..100 {
+                power = montgomery.mul(&power, &base).unwrap();
+            }
+            let expected = IntegerData::from(a.modpow(&Int::from(100), &modulus)).unwrap();
+            assert_eq!(montgomery.from_montgomery(&power).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_montgomery_errors() {
+        assert_eq!(exception_code(Montgomery::new(&IntegerData::from_i32(10))), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(Montgomery::new(&IntegerData::one())), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(Montgomery::new(&IntegerData::from_i32(-7))), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(Montgomery::new(&IntegerData::nan())), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_log10_floor_and_fits() {
+        let log10 = |x: i32, max_digits: u32| IntegerData::from_i32(x).log10_floor_and_fits(max_digits).unwrap();
+        assert_eq!(log10(999, 3), (2, true));
+        assert_eq!(log10(1000, 3), (3, false));
+        assert_eq!(log10(1000, 4), (3, true));
+        assert_eq!(log10(1, 1), (0, true));
+        assert_eq!(log10(0, 1), (0, true));
+        assert_eq!(log10(0, 0), (0, false));
+        assert_eq!(log10(-1000, 4), (3, true));
+        assert_eq!(max_value().log10_floor_and_fits(78).unwrap(), (77, true));
+    }
+
+    #[test]
+    fn test_pow_mod_pow2_matches_modpow() {
+        let bases = [Int::zero(), Int::one(), Int::from(3), Int::from(12345), (Int::one() << 256) - 1];
+        for base in bases.iter() {
+            for exp in [0u32, 1, 2, 15, 16, 255, 12345] {
+                for n in [0, 8, 64, 256] {
+                    let modulus = Int::one() << n;
+                    let expected = IntegerData::from(base.modpow(&Int::from(exp), &modulus)).unwrap();
+                    let result = IntegerData::from(base.clone()).unwrap()
+                        .pow_mod_pow2::<Signaling>(&IntegerData::from_u32(exp), n).unwrap();
+                    assert_eq!(result, expected, "{}^{} mod 2^{}", base, exp, n);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow_mod_pow2_negative() {
+        let result = IntegerData::from_i32(-3).pow_mod_pow2::<Signaling>(&IntegerData::from_u32(3), 8).unwrap();
+        assert_eq!(result, IntegerData::from_u32(229));
+        let base = IntegerData::from_i32(3);
+        assert_eq!(exception_code(base.pow_mod_pow2::<Signaling>(&IntegerData::minus_one(), 8)), Some(ExceptionCode::RangeCheckError));
+        assert!(base.pow_mod_pow2::<Quiet>(&IntegerData::minus_one(), 8).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_weighted_average() {
+        let values = [IntegerData::from_i32(10), IntegerData::from_i32(20), IntegerData::from_i32(30)];
+        let weights = [IntegerData::one(), IntegerData::one(), IntegerData::from_i32(2)];
+        let average = |rounding| IntegerData::weighted_average::<Signaling>(&values, &weights, rounding).unwrap();
+        assert_eq!(average(Round::FloorToNegativeInfinity), IntegerData::from_i32(22));
+        assert_eq!(average(Round::Nearest), IntegerData::from_i32(23));
+        assert_eq!(average(Round::Ceil), IntegerData::from_i32(23));
+    }
+
+    #[test]
+    fn test_weighted_average_errors() {
+        let values = [IntegerData::from_i32(10), IntegerData::from_i32(20)];
+        let result = IntegerData::weighted_average::<Signaling>(&values, &[IntegerData::one()], Round::Nearest);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let zero_weights = [IntegerData::one(), IntegerData::minus_one()];
+        let result = IntegerData::weighted_average::<Signaling>(&values, &zero_weights, Round::Nearest);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+        assert!(IntegerData::weighted_average::<Quiet>(&values, &zero_weights, Round::Nearest).unwrap().is_nan());
+        let nan_weights = [IntegerData::one(), IntegerData::nan()];
+        assert!(IntegerData::weighted_average::<Quiet>(&values, &nan_weights, Round::Nearest).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_result_bits_add() {
+        let value = |x: &IntegerData| x.take_value_of(|v| Some(v.clone())).unwrap();
+        // the bound is exact when signs match
+        let pairs = [
+            (IntegerData::from_i32(5), IntegerData::from_i32(3)),
+            (IntegerData::from_i32(-1), IntegerData::from_i32(-1)),
+            (max_value(), max_value()),
+            (min_value(), min_value()),
+        ];
+        for (x, y) in pairs {
+            let bound = x.result_bits_add::<Signaling>(&y).unwrap();
+            assert_eq!(bound, bitsize(&(value(&x) + value(&y))), "{} + {}", x, y);
+        }
+        let (x, y) = (IntegerData::from_i32(100), IntegerData::from_i32(-3));
+        assert!(bitsize(&(value(&x) + value(&y))) <= x.result_bits_add::<Signaling>(&y).unwrap());
+    }
+
+    #[test]
+    fn test_result_bits_add_nan() {
+        let nan = IntegerData::nan();
+        assert_eq!(exception_code(nan.result_bits_add::<Signaling>(&IntegerData::one())), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(nan.result_bits_add::<Quiet>(&IntegerData::one()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_accumulator_intermediate_overflow() {
+        let max = max_value();
+        let neg_max = max.neg::<Signaling>().unwrap();
+        let mut accumulator = IntegerAccumulator::new();
+        for value in [&max, &max, &max, &neg_max, &neg_max] {
+            accumulator.add(value);
+        }
+        assert_eq!(accumulator.finish::<Signaling>().unwrap(), max);
+    }
+
+    #[test]
+    fn test_accumulator_errors() {
+        let mut accumulator = IntegerAccumulator::new();
+        accumulator.add(&max_value());
+        accumulator.add(&IntegerData::one());
+        assert_eq!(exception_code(accumulator.clone().finish::<Signaling>()), Some(ExceptionCode::IntegerOverflow));
+        assert!(accumulator.finish::<Quiet>().unwrap().is_nan());
+        let mut accumulator = IntegerAccumulator::new();
+        accumulator.add(&IntegerData::nan());
+        accumulator.add(&IntegerData::one());
+        assert_eq!(exception_code(accumulator.clone().finish::<Signaling>()), Some(ExceptionCode::IntegerOverflow));
+        assert!(accumulator.finish::<Quiet>().unwrap().is_nan());
+        assert_eq!(IntegerAccumulator::new().finish::<Signaling>().unwrap(), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_mod_positive() {
+        let modulo = |x: i32, m: i32| IntegerData::from_i32(x).mod_positive::<Signaling>(&IntegerData::from_i32(m)).unwrap();
+        assert_eq!(modulo(-7, -3), IntegerData::from_i32(2));
+        assert_eq!(modulo(7, -3), IntegerData::from_i32(1));
+        assert_eq!(modulo(-7, 3), IntegerData::from_i32(2));
+        let result = IntegerData::from_i32(7).mod_positive::<Signaling>(&IntegerData::zero());
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_digit_sum() {
+        let sum = |x: i32, radix: u32| IntegerData::from_i32(x).digit_sum(radix).unwrap();
+        assert_eq!(sum(12345, 10), IntegerData::from_u32(15));
+        assert_eq!(sum(-12345, 10), IntegerData::from_u32(15));
+        assert_eq!(sum(0, 10), IntegerData::zero());
+        assert_eq!(sum(0xFF, 16), IntegerData::from_u32(30));
+        assert_eq!(sum(0x0102, 256), IntegerData::from_u32(3));
+        assert_eq!(sum(0b1011, 2), IntegerData::from_u32(3));
+        for radix in [0, 1, 257] {
+            assert_eq!(exception_code(IntegerData::one().digit_sum(radix)), Some(ExceptionCode::RangeCheckError));
+        }
+        assert_eq!(exception_code(IntegerData::nan().digit_sum(10)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_digital_root() {
+        let root = |x: i32, radix: u32| IntegerData::from_i32(x).digital_root(radix).unwrap();
+        assert_eq!(root(12345, 10), IntegerData::from_u32(6));
+        assert_eq!(root(0, 10), IntegerData::zero());
+        assert_eq!(root(9, 10), IntegerData::from_u32(9));
+        assert_eq!(root(99999, 10), IntegerData::from_u32(9));
+        assert_eq!(root(0xFF, 16), IntegerData::from_u32(15));
+        assert_eq!(exception_code(IntegerData::one().digital_root(1)), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_scale_pow10() {
+        let scale = |x: i64, exp: i32| IntegerData::from_i64(x).scale_pow10::<Signaling>(exp).unwrap();
+        assert_eq!(scale(1234567, 6), IntegerData::from_i64(1234567000000));
+        assert_eq!(scale(1234567, 0), IntegerData::from_i64(1234567));
+        assert_eq!(scale(1234567, -3), IntegerData::from_i64(1234));
+        assert_eq!(scale(-1234567, -3), IntegerData::from_i64(-1235));
+        assert_eq!(scale(1234567, -1000), IntegerData::zero());
+        assert_eq!(scale(-1234567, -1000), IntegerData::minus_one());
+        assert_eq!(scale(-1234567, i32::MIN), IntegerData::minus_one());
+        assert_eq!(scale(0, i32::MAX), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_scale_pow10_overflow() {
+        assert_eq!(exception_code(max_value().scale_pow10::<Signaling>(1)), Some(ExceptionCode::IntegerOverflow));
+        assert!(max_value().scale_pow10::<Quiet>(1).unwrap().is_nan());
+        assert!(IntegerData::one().scale_pow10::<Quiet>(1000).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_quantize_rounding() {
+        let quantize = |x: i32, bits: usize, signed: bool, rounding: Round| {
+            IntegerData::from_i32(x).quantize(bits, signed, rounding).unwrap()
+        };
+        assert_eq!(quantize(23, 4, false, Round::Nearest), IntegerData::from_i32(24));
+        assert_eq!(quantize(23, 4, false, Round::FloorToZero), IntegerData::from_i32(22));
+        assert_eq!(quantize(23, 4, false, Round::Ceil), IntegerData::from_i32(24));
+        assert_eq!(quantize(5, 4, false, Round::Nearest), IntegerData::from_i32(5));
+        assert_eq!(quantize(-23, 5, true, Round::FloorToNegativeInfinity), IntegerData::from_i32(-24));
+    }
+
+    #[test]
+    fn test_quantize_carry() {
+        let quantize = |x: i32, bits: usize, signed: bool, rounding: Round| {
+            IntegerData::from_i32(x).quantize(bits, signed, rounding).unwrap()
+        };
+        assert_eq!(quantize(255, 4, false, Round::Nearest), IntegerData::from_i32(256));
+        assert_eq!(quantize(241, 4, false, Round::Ceil), IntegerData::from_i32(256));
+        assert_eq!(quantize(127, 4, true, Round::Nearest), IntegerData::from_i32(128));
+        assert_eq!(quantize(-127, 4, true, Round::Nearest), IntegerData::from_i32(-128));
+    }
+
+    #[test]
+    fn test_quantize_clamp() {
+        let quantize = |x: i32, bits: usize, signed: bool, rounding: Round| {
+            IntegerData::from_i32(x).quantize(bits, signed, rounding).unwrap()
+        };
+        assert_eq!(quantize(-5, 4, false, Round::Nearest), IntegerData::zero());
+        assert_eq!(quantize(5, 1, true, Round::Nearest), IntegerData::zero());
+        assert_eq!(quantize(-5, 1, true, Round::Nearest), IntegerData::from_i32(-8));
+        assert_eq!(exception_code(IntegerData::one().quantize(0, false, Round::Nearest)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::nan().quantize(4, false, Round::Nearest)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_round_sig_figs() {
+        let round = |x: i32, figs: u32, rounding: Round| IntegerData::from_i32(x).round_sig_figs(figs, rounding).unwrap();
+        assert_eq!(round(12345, 2, Round::Nearest), IntegerData::from_i32(12000));
+        assert_eq!(round(99999, 2, Round::Nearest), IntegerData::from_i32(100000));
+        assert_eq!(round(-12345, 2, Round::Nearest), IntegerData::from_i32(-12000));
+        assert_eq!(round(-12555, 3, Round::Nearest), IntegerData::from_i32(-12600));
+        assert_eq!(round(12999, 2, Round::FloorToNegativeInfinity), IntegerData::from_i32(12000));
+        assert_eq!(round(12001, 2, Round::Ceil), IntegerData::from_i32(13000));
+        assert_eq!(round(123, 5, Round::Nearest), IntegerData::from_i32(123));
+    }
+
+    #[test]
+    fn test_round_sig_figs_errors() {
+        assert_eq!(exception_code(IntegerData::from_i32(123).round_sig_figs(0, Round::Nearest)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::nan().round_sig_figs(2, Round::Nearest)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_rem_with_sign() {
+        let rem = |x: i32, y: i32, sign: RemSign| {
+            IntegerData::from_i32(x).rem_with_sign::<Signaling>(&IntegerData::from_i32(y), sign).unwrap()
+        };
+        let cases = [
+            (-7, 3, [-1, 2, 2]),
+            (-7, -3, [-1, -1, 2]),
+            (7, -3, [1, -2, 1]),
+            (7, 3, [1, 1, 1]),
+        ];
+        for (x, y, expected) in cases {
+            assert_eq!(rem(x, y, RemSign::Dividend), IntegerData::from_i32(expected[0]), "{} rem {}", x, y);
+            assert_eq!(rem(x, y, RemSign::Divisor), IntegerData::from_i32(expected[1]), "{} mod {}", x, y);
+            assert_eq!(rem(x, y, RemSign::NonNegative), IntegerData::from_i32(expected[2]), "{} rem_euclid {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_rem_with_sign_by_zero() {
+        let value = IntegerData::from_i32(7);
+        for sign in [RemSign::Dividend, RemSign::Divisor, RemSign::NonNegative] {
+            let result = value.rem_with_sign::<Signaling>(&IntegerData::zero(), sign);
+            assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+            assert!(value.rem_with_sign::<Quiet>(&IntegerData::zero(), sign).unwrap().is_nan());
+        }
+    }
+
+    fn integers(items: &[i32]) -> Vec<IntegerData> {
+        items.iter().map(|x| IntegerData::from_i32(*x)).collect()
+    }
+
+    #[test]
+    fn test_to_mixed_radix() {
+        let digits = |x: i32, radices: &[i32]| IntegerData::from_i32(x).to_mixed_radix(&integers(radices)).unwrap();
+        assert_eq!(digits(3661, &[60, 60]), integers(&[1, 1, 1]));
+        assert_eq!(digits(3661, &[]), integers(&[3661]));
+        assert_eq!(digits(-1, &[60]), integers(&[59, -1]));
+        assert_eq!(digits(0, &[10, 10]), integers(&[0, 0, 0]));
+    }
+
+    #[test]
+    fn test_to_mixed_radix_errors() {
+        let value = IntegerData::from_i32(3661);
+        assert_eq!(exception_code(value.to_mixed_radix(&integers(&[60, 0]))), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(value.to_mixed_radix(&integers(&[-60]))), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(value.to_mixed_radix(&[IntegerData::nan()])), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_from_mixed_radix_round_trip() {
+        let radices = integers(&[24, 60, 60]);
+        for x in [0, 1, 59, 3661, 86399, 86400, 1_000_000, -1, -3661] {
+            let digits = IntegerData::from_i32(x).to_mixed_radix(&radices).unwrap();
+            assert_eq!(IntegerData::from_mixed_radix(&digits, &radices).unwrap(), IntegerData::from_i32(x));
+        }
+    }
+
+    #[test]
+    fn test_from_mixed_radix_errors() {
+        let radices = integers(&[60, 60]);
+        let result = IntegerData::from_mixed_radix(&integers(&[60, 0, 1]), &radices);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let result = IntegerData::from_mixed_radix(&integers(&[-1, 0, 1]), &radices);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let result = IntegerData::from_mixed_radix(&integers(&[1, 1]), &radices);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let result = IntegerData::from_mixed_radix(&[], &[]);
+        assert_eq!(exception_code(result), Some(ExceptionCode::RangeCheckError));
+        let result = IntegerData::from_mixed_radix(&[IntegerData::zero(), max_value()], &integers(&[2]));
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_reciprocal_fixed() {
+        let reciprocal = |x: i32, decimals: u32, rounding: Round| {
+            IntegerData::from_i32(x).reciprocal_fixed(decimals, rounding).unwrap()
+        };
+        assert_eq!(reciprocal(3, 6, Round::FloorToNegativeInfinity), IntegerData::from_i32(333333));
+        assert_eq!(reciprocal(6, 6, Round::Nearest), IntegerData::from_i32(166667));
+        assert_eq!(reciprocal(6, 6, Round::FloorToZero), IntegerData::from_i32(166666));
+        assert_eq!(reciprocal(-3, 6, Round::FloorToNegativeInfinity), IntegerData::from_i32(-333334));
+        assert_eq!(reciprocal(-3, 6, Round::FloorToZero), IntegerData::from_i32(-333333));
+        assert_eq!(reciprocal(1, 0, Round::Nearest), IntegerData::one());
+    }
+
+    #[test]
+    fn test_reciprocal_fixed_errors() {
+        let code = |x: &IntegerData, decimals: u32| exception_code(x.reciprocal_fixed(decimals, Round::Nearest));
+        assert_eq!(code(&IntegerData::zero(), 6), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(code(&max_value(), 161), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(code(&IntegerData::one(), 100), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(code(&IntegerData::nan(), 6), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_fixed_mul() {
+        let fixed = |x: Int| IntegerData::from(x).unwrap();
+        let mul = |x: &IntegerData, y: &IntegerData, rounding: Round| x.fixed_mul::<Signaling>(y, 64, rounding);
+        // 1.5 * 2.5 == 3.75 in Q64.64
+        let product = mul(&fixed(Int::from(3) << 63), &fixed(Int::from(5) << 63), Round::FloorToZero).unwrap();
+        assert_eq!(product, fixed(Int::from(15) << 62));
+        let one = IntegerData::one();
+        assert_eq!(mul(&one, &one, Round::FloorToZero).unwrap(), IntegerData::zero());
+        assert_eq!(mul(&one, &one, Round::Ceil).unwrap(), IntegerData::one());
+        assert_eq!(mul(&IntegerData::minus_one(), &one, Round::FloorToNegativeInfinity).unwrap(), IntegerData::minus_one());
+        assert_eq!(mul(&IntegerData::minus_one(), &one, Round::FloorToZero).unwrap(), IntegerData::zero());
+    }
+
+    #[test]
+    fn test_fixed_mul_full_precision() {
+        let pow2 = |n: usize| IntegerData::from(Int::one() << n).unwrap();
+        let product = pow2(200).fixed_mul::<Signaling>(&pow2(100), 64, Round::FloorToZero).unwrap();
+        assert_eq!(product, pow2(236));
+        let result = pow2(200).fixed_mul::<Signaling>(&pow2(200), 64, Round::FloorToZero);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+        assert!(pow2(200).fixed_mul::<Quiet>(&pow2(200), 64, Round::FloorToZero).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_fixed_div() {
+        let div = |x: i32, y: i32, rounding: Round| {
+            IntegerData::from_i32(x).fixed_div::<Signaling>(&IntegerData::from_i32(y), 8, rounding).unwrap()
+        };
+        // 1.0 / 3.0 and 2.0 / 3.0 in Q8
+        assert_eq!(div(256, 768, Round::FloorToNegativeInfinity), IntegerData::from_i32(85));
+        assert_eq!(div(256, 768, Round::Ceil), IntegerData::from_i32(86));
+        assert_eq!(div(256, 768, Round::Nearest), IntegerData::from_i32(85));
+        assert_eq!(div(512, 768, Round::Nearest), IntegerData::from_i32(171));
+        assert_eq!(div(-256, 768, Round::FloorToNegativeInfinity), IntegerData::from_i32(-86));
+        let one = IntegerData::from(Int::one() << 64).unwrap();
+        let three = IntegerData::from(Int::from(3) << 64).unwrap();
+        let third = one.fixed_div::<Signaling>(&three, 64, Round::FloorToNegativeInfinity).unwrap();
+        assert_eq!(third, IntegerData::from_u64(6148914691236517205));
+    }
+
+    #[test]
+    fn test_fixed_div_errors() {
+        let value = IntegerData::from_i32(256);
+        let result = value.fixed_div::<Signaling>(&IntegerData::zero(), 8, Round::Nearest);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+        assert!(value.fixed_div::<Quiet>(&IntegerData::zero(), 8, Round::Nearest).unwrap().is_nan());
+        let result = max_value().fixed_div::<Signaling>(&IntegerData::one(), 8, Round::Nearest);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_rational_approx() {
+        let approx = |n: i64, d: i64, max_denom: i64| {
+            IntegerData::from_i64(n).rational_approx(&IntegerData::from_i64(d), &IntegerData::from_i64(max_denom)).unwrap()
+        };
+        let fraction = |p: i64, q: i64| (IntegerData::from_i64(p), IntegerData::from_i64(q));
+        let (pi, scale) = (3_141_592_653_589_793, 1_000_000_000_000_000);
+        assert_eq!(approx(pi, scale, 113), fraction(355, 113));
+        assert_eq!(approx(pi, scale, 10), fraction(22, 7));
+        assert_eq!(approx(pi, scale, 1), fraction(3, 1));
+        assert_eq!(approx(355, 113, 1000), fraction(355, 113));
+        assert_eq!(approx(3, 7, 100), fraction(3, 7));
+    }
+
+    #[test]
+    fn test_rational_approx_semiconvergent() {
+        let approx = |n: i64, d: i64, max_denom: i64| {
+            IntegerData::from_i64(n).rational_approx(&IntegerData::from_i64(d), &IntegerData::from_i64(max_denom)).unwrap()
+        };
+        let fraction = |p: i64, q: i64| (IntegerData::from_i64(p), IntegerData::from_i64(q));
+        let (pi, scale) = (3_141_592_653_589_793, 1_000_000_000_000_000);
+        // convergents of pi are 22/7 and 355/113, best fraction with denominator up to 100 lies between
+        assert_eq!(approx(pi, scale, 100), fraction(311, 99));
+        assert_eq!(approx(-pi, scale, 100), fraction(-311, 99));
+        assert_eq!(approx(pi, -scale, 100), fraction(-311, 99));
+        assert_eq!(approx(1, 3, 2), fraction(1, 2));
+        assert_eq!(approx(2, 3, 1), fraction(1, 1));
+    }
+
+    #[test]
+    fn test_rational_approx_errors() {
+        let one = IntegerData::one();
+        assert_eq!(exception_code(one.rational_approx(&one, &IntegerData::zero())), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(one.rational_approx(&IntegerData::zero(), &one)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::nan().rational_approx(&one, &one)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_reduce_fraction() {
+        let reduce = |n: i32, d: i32| IntegerData::reduce_fraction(&IntegerData::from_i32(n), &IntegerData::from_i32(d)).unwrap();
+        let fraction = |p: i32, q: i32| (IntegerData::from_i32(p), IntegerData::from_i32(q));
+        assert_eq!(reduce(6, -4), fraction(-3, 2));
+        assert_eq!(reduce(-6, 4), fraction(-3, 2));
+        assert_eq!(reduce(-6, -4), fraction(3, 2));
+        assert_eq!(reduce(0, -5), fraction(0, 1));
+        assert_eq!(reduce(7, 3), fraction(7, 3));
+    }
+
+    #[test]
+    fn test_reduce_fraction_errors() {
+        let one = IntegerData::one();
+        assert_eq!(exception_code(IntegerData::reduce_fraction(&one, &IntegerData::zero())), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::reduce_fraction(&IntegerData::nan(), &one)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    fn from_continued_fraction(terms: &[IntegerData]) -> (Int, Int) {
+        let (mut p0, mut q0, mut p1, mut q1) = (Int::zero(), Int::one(), Int::one(), Int::zero());
+        for term in terms {
+            let term = term.take_value_of(|x| Some(x.clone())).unwrap();
+            let p2 = &p0 + &term * &p1;
+            let q2 = &q0 + &term * &q1;
+            p0 = std::mem::replace(&mut p1, p2);
+            q0 = std::mem::replace(&mut q1, q2);
+        }
+        (p1, q1)
+    }
+
+    #[test]
+    fn test_continued_fraction() {
+        let expand = |n: i32, d: i32, max_terms: usize| {
+            IntegerData::continued_fraction(&IntegerData::from_i32(n), &IntegerData::from_i32(d), max_terms).unwrap()
+        };
+        assert_eq!(expand(355, 113, 10), integers(&[3, 7, 16]));
+        assert_eq!(expand(355, 113, 2), integers(&[3, 7]));
+        assert_eq!(expand(355, 113, 0), integers(&[]));
+        assert_eq!(expand(-355, 113, 10), integers(&[-4, 1, 6, 16]));
+        assert_eq!(expand(355, -113, 10), integers(&[-4, 1, 6, 16]));
+        assert_eq!(expand(0, 5, 10), integers(&[0]));
+        // both canonical and non-canonical expansions give the same fraction
+        let expected = (Int::from(355), Int::from(113));
+        assert_eq!(from_continued_fraction(&integers(&[3, 7, 16])), expected);
+        assert_eq!(from_continued_fraction(&integers(&[3, 7, 15, 1])), expected);
+        assert_eq!(from_continued_fraction(&expand(-355, 113, 10)), (Int::from(-355), Int::from(113)));
+    }
+
+    #[test]
+    fn test_continued_fraction_errors() {
+        let result = IntegerData::continued_fraction(&IntegerData::one(), &IntegerData::zero(), 10);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+        let result = IntegerData::continued_fraction(&IntegerData::nan(), &IntegerData::one(), 10);
+        assert_eq!(exception_code(result), Some(ExceptionCode::IntegerOverflow));
+    }
+
+        #[test]
+        fn test_pow_mod_pow2_huge_width() {
+            let result = IntegerData::from_i32(3).pow_mod_pow2::<Signaling>(&IntegerData::from_u32(2), usize::MAX).unwrap();
+            assert_eq!(result, IntegerData::from_u32(9));
+            let result = max_value().pow_mod_pow2::<Signaling>(&IntegerData::one(), usize::MAX).unwrap();
+            assert_eq!(result, max_value());
+        }
 }
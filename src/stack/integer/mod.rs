@@ -30,12 +30,29 @@ use std::cmp::Ordering;
 #[macro_use]
 pub mod behavior;
 mod fmt;
+mod traits;
 
 type Int = num::BigInt;
 
+/// Origin of NaN value. Zero-sized unless "nan-provenance" feature is enabled.
+/// Doesn't take part in comparison: all NaNs are equal to each other.
+#[derive(Clone, Copy, Debug, Default)]
+struct NanReason {
+    #[cfg(feature = "nan-provenance")]
+    reason: Option<&'static str>,
+}
+
+impl PartialEq for NanReason {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for NanReason {}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum IntegerValue {
-    NaN,
+    NaN(NanReason),
     Value(Int)
 }
 
@@ -50,6 +67,15 @@ impl cmp::PartialOrd for IntegerValue {
     }
 }
 
+/// Class of value as determined by IntegerData::classify().
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerClass {
+    Nan,
+    Zero,
+    Positive,
+    Negative,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IntegerData {
     value: IntegerValue
@@ -102,10 +128,45 @@ impl IntegerData {
     #[inline]
     pub fn nan() -> IntegerData {
         IntegerData {
-            value: IntegerValue::NaN
+            value: IntegerValue::NaN(NanReason::default())
         }
     }
 
+    /// Records a reason of NaN value, if not recorded yet.
+    #[cfg(feature = "nan-provenance")]
+    #[inline]
+    pub(crate) fn with_nan_reason(mut self, reason: &'static str) -> IntegerData {
+        if let IntegerValue::NaN(ref mut nan) = self.value {
+            nan.reason.get_or_insert(reason);
+        }
+        self
+    }
+
+    /// Records a reason of NaN value, if not recorded yet.
+    #[cfg(not(feature = "nan-provenance"))]
+    #[inline]
+    pub(crate) fn with_nan_reason(self, _reason: &'static str) -> IntegerData {
+        self
+    }
+
+    /// Returns the reason why value became NaN.
+    #[cfg(feature = "nan-provenance")]
+    #[inline]
+    pub fn nan_reason(&self) -> Option<&str> {
+        match self.value {
+            IntegerValue::NaN(ref nan) => nan.reason,
+            IntegerValue::Value(_) => None,
+        }
+    }
+
+    /// Returns the reason why value became NaN. Always None unless
+    /// "nan-provenance" feature is enabled.
+    #[cfg(not(feature = "nan-provenance"))]
+    #[inline]
+    pub fn nan_reason(&self) -> Option<&str> {
+        None
+    }
+
     /// Constructs mask for bits
     /// it must be refactored to simplify
     pub fn mask(bits: usize) -> Self {
@@ -114,6 +175,15 @@ impl IntegerData {
             .sub::<Quiet>(&IntegerData::one()).unwrap()
     }
 
+    /// Constructs unsigned value of n ones (2^n - 1).
+    /// Returns IntegerOverflow for n > 256 since the result doesn't fit signed 257 bits.
+    pub fn ones(n: usize) -> Result<IntegerData> {
+        if n > 256 {
+            return err!(ExceptionCode::IntegerOverflow, "{} ones cannot fit in 256 bits", n)
+        }
+        IntegerData::from((Int::one() << n) - 1)
+    }
+
     /// Clears value (sets to 0).
     #[inline]
     pub fn withdraw(&mut self) -> IntegerData {
@@ -129,21 +199,57 @@ impl IntegerData {
     /// Checks if value is a Not-a-Number (NaN).
     #[inline]
     pub fn is_nan(&self) -> bool {
-        self.value == IntegerValue::NaN
+        matches!(self.value, IntegerValue::NaN(_))
+    }
+
+    /// Checks if value is a finite number (not a NaN).
+    #[inline]
+    pub fn is_finite(&self) -> bool {
+        !self.is_nan()
+    }
+
+    /// Returns a copy of value if it is finite; otherwise range check error, as check_neg() does for NaN.
+    #[inline]
+    pub fn require_finite(&self) -> Result<IntegerData> {
+        match self.value {
+            IntegerValue::NaN(_) => err!(ExceptionCode::RangeCheckError, "not a number"),
+            IntegerValue::Value(_) => Ok(self.clone()),
+        }
+    }
+
+    /// Calls a given closure only if value is finite; otherwise returns NaN.
+    #[inline]
+    pub fn and_then_finite<F>(&self, f: F) -> Result<IntegerData>
+    where
+        F: FnOnce(&IntegerData) -> Result<IntegerData>,
+    {
+        match self.value {
+            IntegerValue::NaN(_) => Ok(IntegerData::nan()),
+            IntegerValue::Value(_) => f(self),
+        }
+    }
+
+    /// Returns a copy of value, or a given default if value is NaN.
+    #[inline]
+    pub fn nan_to(&self, default: &IntegerData) -> IntegerData {
+        match self.value {
+            IntegerValue::NaN(_) => default.clone(),
+            IntegerValue::Value(_) => self.clone(),
+        }
     }
 
     /// Checks if value is negative (less than zero).
     #[inline]
     pub fn is_neg(&self) -> bool {
         match &self.value {
-            IntegerValue::NaN => false,
+            IntegerValue::NaN(_) => false,
             IntegerValue::Value(ref value) => value.is_negative()
         }
     }
 
     pub fn check_neg(&self) -> Result<()> {
         match self.value {
-            IntegerValue::NaN => err!(ExceptionCode::RangeCheckError, "not a number"),
+            IntegerValue::NaN(_) => err!(ExceptionCode::RangeCheckError, "not a number"),
             IntegerValue::Value(ref value) => {
                 if value.is_negative() {
                     err!(ExceptionCode::RangeCheckError, "{} is negative", value)
@@ -158,11 +264,60 @@ impl IntegerData {
     #[inline]
     pub fn is_zero(&self) -> bool {
         match &self.value {
-            IntegerValue::NaN => false,
+            IntegerValue::NaN(_) => false,
             IntegerValue::Value(ref value) => value.is_zero()
         }
     }
 
+    /// Determines class of value (NaN, zero, positive or negative) in a single check.
+    #[inline]
+    pub fn classify(&self) -> IntegerClass {
+        match self.sign() {
+            None => IntegerClass::Nan,
+            Some(num::bigint::Sign::NoSign) => IntegerClass::Zero,
+            Some(num::bigint::Sign::Plus) => IntegerClass::Positive,
+            Some(num::bigint::Sign::Minus) => IntegerClass::Negative,
+        }
+    }
+
+    /// Returns sign of value as -1, 0 or 1, or None for NaN.
+    #[inline]
+    pub fn sign_i8(&self) -> Option<i8> {
+        match self.classify() {
+            IntegerClass::Nan => None,
+            IntegerClass::Zero => Some(0),
+            IntegerClass::Positive => Some(1),
+            IntegerClass::Negative => Some(-1),
+        }
+    }
+
+    /// Inspects sign tag of value without touching its digits.
+    #[inline]
+    fn sign(&self) -> Option<num::bigint::Sign> {
+        match &self.value {
+            IntegerValue::NaN(_) => None,
+            IntegerValue::Value(ref value) => Some(value.sign()),
+        }
+    }
+
+    /// Checks if value is a canonical TVM boolean: 0 (false) or -1 (true).
+    #[inline]
+    pub fn is_tvm_bool(&self) -> bool {
+        self.is_zero() || self == &IntegerData::minus_one()
+    }
+
+    /// Converts canonical TVM boolean into bool: 0 into false, -1 into true.
+    pub fn to_tvm_bool(&self) -> Result<bool> {
+        match self.value {
+            IntegerValue::NaN(_) => err!(ExceptionCode::IntegerOverflow, "not a number"),
+            IntegerValue::Value(ref value) if value.is_zero() => Ok(false),
+            IntegerValue::Value(_) if self.is_tvm_bool() => Ok(true),
+            IntegerValue::Value(ref value) => {
+                err!(ExceptionCode::TypeCheckError, "{} is not a boolean", value)
+            }
+        }
+    }
+
     /// constuct
     pub fn from_unsigned_bytes_be(data: impl AsRef<[u8]>) -> Self {
         Self {
@@ -182,6 +337,38 @@ impl IntegerData {
         }
     }
 
+    /// Checks if lo <= value <= hi taking in account behavior of operation.
+    pub fn in_range<T: OperationBehavior>(&self, lo: &IntegerData, hi: &IntegerData) -> Result<bool> {
+        if lo.compare::<T>(hi)? == Some(Ordering::Greater) {
+            return err!(ExceptionCode::RangeCheckError, "{} is greater than {}", lo, hi)
+        }
+        let above_lo = matches!(self.compare::<T>(lo)?, Some(Ordering::Greater | Ordering::Equal));
+        let below_hi = matches!(self.compare::<T>(hi)?, Some(Ordering::Less | Ordering::Equal));
+        Ok(above_lo && below_hi)
+    }
+
+    /// Returns value as index if 0 <= value < bound; otherwise None (including NaN).
+    #[inline]
+    pub fn as_index(&self, bound: usize) -> Option<usize> {
+        match &self.value {
+            IntegerValue::NaN(_) => None,
+            IntegerValue::Value(ref value) => {
+                num::ToPrimitive::to_usize(value).filter(|index| *index < bound)
+            }
+        }
+    }
+
+    /// Compares absolute values without allocating them. Returns None if any value is NaN.
+    #[inline]
+    pub fn cmp_magnitude(&self, other: &IntegerData) -> ResultOpt<Ordering> {
+        match (&self.value, &other.value) {
+            (IntegerValue::Value(l), IntegerValue::Value(r)) => Ok(Some(
+                l.bits().cmp(&r.bits()).then_with(|| l.magnitude().cmp(r.magnitude()))
+            )),
+            _ => Ok(None)
+        }
+    }
+
     /// Returns true if signed value fits into a given bits size; otherwise false.
     #[inline]
     pub fn fits_in(&self, bits: usize) -> Result<bool> {
@@ -194,6 +381,36 @@ impl IntegerData {
         Ok(!self.is_neg() && self.ubitsize()? <= bits)
     }
 
+    /// Returns value if signed value fits into a given bits size; otherwise NaN
+    /// taking in account behavior of operation.
+    #[inline]
+    pub fn qfits<T: OperationBehavior>(&self, bits: usize) -> Result<IntegerData> {
+        self.fits_or_nan::<T>(bits, IntegerData::fits_in)
+    }
+
+    /// Returns value if unsigned value fits into a given bits size; otherwise NaN
+    /// taking in account behavior of operation.
+    #[inline]
+    pub fn qufits<T: OperationBehavior>(&self, bits: usize) -> Result<IntegerData> {
+        self.fits_or_nan::<T>(bits, IntegerData::ufits_in)
+    }
+
+    fn fits_or_nan<T: OperationBehavior>(
+        &self,
+        bits: usize,
+        op_fit: fn(&IntegerData, usize) -> Result<bool>
+    ) -> Result<IntegerData> {
+        if self.is_nan() {
+            on_nan_parameter!(T)?;
+            return Ok(IntegerData::nan())
+        }
+        if !op_fit(self, bits)? {
+            on_integer_overflow!(T)?;
+            return Ok(IntegerData::nan())
+        }
+        Ok(self.clone())
+    }
+
     /// Determines a fewest bits necessary to express signed value.
     #[inline]
     pub fn bitsize(&self) -> Result<usize> {
@@ -211,6 +428,18 @@ impl IntegerData {
         })
     }
 
+    /// Determines a fewest bits necessary to express value as signed or unsigned.
+    /// NaN raises IntegerOverflow regardless of signedness.
+    pub fn min_bits(&self, signed: bool) -> Result<usize> {
+        utils::process_value(self, |value| {
+            match signed {
+                true => Ok(utils::bitsize(value)),
+                false if value.is_negative() => err!(ExceptionCode::RangeCheckError, "{} is negative", value),
+                false => Ok(value.bits() as usize),
+            }
+        })
+    }
+
     pub fn as_slice<T: Encoding>(&self, bits: usize) -> Result<SliceData> {
         SliceData::load_builder(self.as_builder::<T>(bits)?)
     }
@@ -244,7 +473,7 @@ pub mod utils {
         F: Fn(&Int) -> Result<R>,
     {
         match value.value {
-            IntegerValue::NaN => {
+            IntegerValue::NaN(_) => {
                 err!(ExceptionCode::IntegerOverflow)
             }
             IntegerValue::Value(ref value) => call_on_valid(value),
@@ -256,7 +485,7 @@ pub mod utils {
     macro_rules! extract_value {
         ($T: ident, $v: ident, $nan_constructor: ident) => {
             match $v.value {
-                IntegerValue::NaN => {
+                IntegerValue::NaN(_) => {
                     on_nan_parameter!($T)?;
                     return Ok($nan_constructor());
                 },
@@ -313,7 +542,7 @@ pub mod utils {
     {
         IntegerData::from(result).or_else(|_| {
             on_integer_overflow!(T)?;
-            Ok(nan_constructor())
+            Ok(nan_constructor().with_nan_reason("integer overflow"))
         })
     }
 
@@ -329,7 +558,8 @@ pub mod utils {
             Ok(r1) => Ok((r1, IntegerData::from(r2)?)),
             Err(_) => {
                 on_integer_overflow!(T)?;
-                Ok(nan_constructor())
+                let (r1, r2) = nan_constructor();
+                Ok((r1.with_nan_reason("integer overflow"), r2.with_nan_reason("integer overflow")))
             },
         }
     }
@@ -392,6 +622,184 @@ pub mod conversion;
 pub mod serialization;
 pub mod math;
 pub mod bitlogics;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-
+    /// Extracts TVM exception code from a failed result.
+    pub(super) fn exception_code<T: std::fmt::Debug>(result: Result<T>) -> Option<ExceptionCode> {
+        crate::error::tvm_exception_code(&result.unwrap_err())
+    }
+
+    #[test]
+    fn test_is_finite() {
+        assert!(IntegerData::zero().is_finite());
+        assert!(IntegerData::minus_one().is_finite());
+        assert!(!IntegerData::nan().is_finite());
+    }
+
+    #[test]
+    fn test_and_then_finite_short_circuits_on_nan() {
+        let mut called = false;
+        let result = IntegerData::nan().and_then_finite(|_| {
+            called = true;
+            Ok(IntegerData::one())
+        }).unwrap();
+        assert!(result.is_nan());
+        assert!(!called);
+
+        let result = IntegerData::from_i32(5)
+            .and_then_finite(|x| x.add::<Signaling>(&IntegerData::one()))
+            .unwrap();
+        assert_eq!(result, IntegerData::from_i32(6));
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(IntegerData::nan().classify(), IntegerClass::Nan);
+        assert_eq!(IntegerData::zero().classify(), IntegerClass::Zero);
+        assert_eq!(IntegerData::from_i32(5).classify(), IntegerClass::Positive);
+        assert_eq!(IntegerData::from_i32(-5).classify(), IntegerClass::Negative);
+    }
+
+    #[test]
+    fn test_min_bits() {
+        let value = IntegerData::from_i32(127);
+        assert_eq!(value.min_bits(true).unwrap(), 8);
+        assert_eq!(value.min_bits(false).unwrap(), 7);
+        let value = IntegerData::from_i32(-128);
+        assert_eq!(value.min_bits(true).unwrap(), 8);
+        assert_eq!(exception_code(value.min_bits(false)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::nan().min_bits(true)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::nan().min_bits(false)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_nan_to() {
+        let default = IntegerData::from_i32(42);
+        assert_eq!(IntegerData::nan().nan_to(&default), default);
+        assert_eq!(IntegerData::from_i32(-7).nan_to(&default), IntegerData::from_i32(-7));
+    }
+
+    #[test]
+    fn test_qfits() {
+        let value = IntegerData::from_i32(127);
+        assert_eq!(value.qfits::<Quiet>(8).unwrap(), value);
+        assert_eq!(value.qfits::<Signaling>(8).unwrap(), value);
+        let value = IntegerData::from_i32(128);
+        assert!(value.qfits::<Quiet>(8).unwrap().is_nan());
+        assert_eq!(exception_code(value.qfits::<Signaling>(8)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_qufits() {
+        let value = IntegerData::from_i32(255);
+        assert_eq!(value.qufits::<Quiet>(8).unwrap(), value);
+        assert_eq!(value.qufits::<Signaling>(8).unwrap(), value);
+        let value = IntegerData::minus_one();
+        assert!(value.qufits::<Quiet>(8).unwrap().is_nan());
+        assert_eq!(exception_code(value.qufits::<Signaling>(8)), Some(ExceptionCode::IntegerOverflow));
+        assert!(IntegerData::nan().qufits::<Quiet>(8).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_cmp_magnitude() {
+        let compare = |a: i32, b: i32| IntegerData::from_i32(a).cmp_magnitude(&IntegerData::from_i32(b)).unwrap();
+        assert_eq!(compare(-5, 3), Some(Ordering::Greater));
+        assert_eq!(compare(3, -5), Some(Ordering::Less));
+        assert_eq!(compare(-5, 5), Some(Ordering::Equal));
+        assert_eq!(compare(0, 0), Some(Ordering::Equal));
+        assert_eq!(IntegerData::nan().cmp_magnitude(&IntegerData::one()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_ones() {
+        assert_eq!(IntegerData::ones(8).unwrap(), IntegerData::from_i32(255));
+        assert_eq!(IntegerData::ones(0).unwrap(), IntegerData::zero());
+        assert_eq!(IntegerData::ones(256).unwrap().ubitsize().unwrap(), 256);
+        assert_eq!(exception_code(IntegerData::ones(257)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::ones(usize::MAX)), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[cfg(feature = "nan-provenance")]
+    #[test]
+    fn test_nan_reason() {
+        let max = IntegerData::from((Int::one() << 256) - 1).unwrap();
+        let overflow = max.add::<Quiet>(&IntegerData::one()).unwrap();
+        let (division_by_zero, _) = max.div::<Quiet>(&IntegerData::zero(), math::Round::FloorToZero).unwrap();
+        assert_eq!(overflow.nan_reason(), Some("integer overflow"));
+        assert_eq!(division_by_zero.nan_reason(), Some("division by zero"));
+        assert_ne!(overflow.nan_reason(), division_by_zero.nan_reason());
+        assert_eq!(IntegerData::nan().nan_reason(), None);
+        assert_eq!(max.nan_reason(), None);
+    }
+
+    #[cfg(not(feature = "nan-provenance"))]
+    #[test]
+    fn test_nan_reason_is_zero_sized() {
+        assert_eq!(mem::size_of::<NanReason>(), 0);
+        assert_eq!(IntegerData::nan().nan_reason(), None);
+    }
+
+    #[test]
+    fn test_in_range() {
+        let (lo, hi) = (IntegerData::from_i32(-3), IntegerData::from_i32(7));
+        let in_range = |value: i32| IntegerData::from_i32(value).in_range::<Signaling>(&lo, &hi).unwrap();
+        assert!(in_range(-3));
+        assert!(in_range(7));
+        assert!(in_range(0));
+        assert!(!in_range(-4));
+        assert!(!in_range(8));
+    }
+
+    #[test]
+    fn test_in_range_errors() {
+        let (lo, hi) = (IntegerData::from_i32(7), IntegerData::from_i32(-3));
+        assert_eq!(exception_code(IntegerData::zero().in_range::<Signaling>(&lo, &hi)), Some(ExceptionCode::RangeCheckError));
+        let (lo, hi) = (hi, lo);
+        assert_eq!(exception_code(IntegerData::nan().in_range::<Signaling>(&lo, &hi)), Some(ExceptionCode::IntegerOverflow));
+        assert!(!IntegerData::nan().in_range::<Quiet>(&lo, &hi).unwrap());
+    }
+
+    #[test]
+    fn test_sign_i8() {
+        assert_eq!(IntegerData::from_i32(42).sign_i8(), Some(1));
+        assert_eq!(IntegerData::zero().sign_i8(), Some(0));
+        assert_eq!(IntegerData::from_i32(-42).sign_i8(), Some(-1));
+        assert_eq!(IntegerData::nan().sign_i8(), None);
+    }
+
+    #[test]
+    fn test_require_finite() {
+        assert_eq!(IntegerData::from_i32(5).require_finite().unwrap(), IntegerData::from_i32(5));
+        assert_eq!(exception_code(IntegerData::nan().require_finite()), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_tvm_bool() {
+        assert!(IntegerData::zero().is_tvm_bool());
+        assert!(IntegerData::minus_one().is_tvm_bool());
+        assert!(!IntegerData::one().is_tvm_bool());
+        assert!(!IntegerData::from_i32(5).is_tvm_bool());
+        assert!(!IntegerData::nan().is_tvm_bool());
+        assert!(!IntegerData::zero().to_tvm_bool().unwrap());
+        assert!(IntegerData::minus_one().to_tvm_bool().unwrap());
+        assert_eq!(exception_code(IntegerData::one().to_tvm_bool()), Some(ExceptionCode::TypeCheckError));
+        assert_eq!(exception_code(IntegerData::from_i32(5).to_tvm_bool()), Some(ExceptionCode::TypeCheckError));
+        assert_eq!(exception_code(IntegerData::nan().to_tvm_bool()), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_as_index() {
+        assert_eq!(IntegerData::zero().as_index(10), Some(0));
+        assert_eq!(IntegerData::from_i32(9).as_index(10), Some(9));
+        assert_eq!(IntegerData::from_i32(10).as_index(10), None);
+        assert_eq!(IntegerData::zero().as_index(0), None);
+        assert_eq!(IntegerData::minus_one().as_index(10), None);
+        assert_eq!(IntegerData::nan().as_index(10), None);
+        assert_eq!(IntegerData::from(Int::one() << 200).unwrap().as_index(usize::MAX), None);
+    }
+}
@@ -11,10 +11,26 @@
 * limitations under the License.
 */
 
-use crate::stack::{
-    integer::IntegerData,
-    serialization::{Serializer, Deserializer}
+use crate::{
+    error::TvmError,
+    stack::{
+        integer::{
+            Int, IntegerData,
+            behavior::{OperationBehavior, Signaling},
+        },
+        serialization::{Serializer, Deserializer}
+    },
+    types::Exception,
 };
+use num_traits::{One, Signed, Zero};
+use ton_types::{error, ExceptionCode, Result};
+
+/// Byte order of raw integer representation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Endian {
+    Big,
+    Little,
+}
 
 pub trait Encoding : Serializer<IntegerData> + Deserializer<IntegerData> {
     fn new(length_in_bits: usize) -> Self;
@@ -31,4 +47,383 @@ pub use self::unsigned_big_endian::UnsignedIntegerBigEndianEncoding;
 pub use self::signed_big_endian::SignedIntegerBigEndianEncoding;
 pub use self::signed_little_endian::SignedIntegerLittleEndianEncoding;
 
+impl IntegerData {
+    /// Serializes value into compact form: values in [-64, 63] take a single byte,
+    /// others are prefixed with a byte 0x80 | length of signed big-endian representation.
+    pub fn to_compact(&self) -> Result<Vec<u8>> {
+        let bytes = self.take_value_of(|x| Some(x.to_signed_bytes_be()))?;
+        if bytes.len() == 1 && (-64..=63).contains(&(bytes[0] as i8)) {
+            return Ok(vec![bytes[0] & 0x7F])
+        }
+        let mut result = Vec::with_capacity(bytes.len() + 1);
+        result.push(0x80 | bytes.len() as u8);
+        result.extend_from_slice(&bytes);
+        Ok(result)
+    }
+
+    /// Deserializes value from compact form produced by to_compact().
+    pub fn from_compact(data: &[u8]) -> Result<IntegerData> {
+        match data.split_first() {
+            Some((&tag, [])) if tag & 0x80 == 0 => {
+                Ok(IntegerData::from_i32(((tag << 1) as i8 >> 1) as i32))
+            }
+            Some((&tag, bytes)) if tag & 0x80 != 0 && !bytes.is_empty()
+                && bytes.len() == (tag & 0x7F) as usize => {
+                IntegerData::from(Int::from_signed_bytes_be(bytes))
+            }
+            _ => err!(ExceptionCode::TypeCheckError, "invalid compact encoding")
+        }
+    }
+
+    /// Checks if big-endian bytes are the minimal representation of value,
+    /// i.e. contain no redundant leading sign bytes.
+    pub fn is_canonical_encoding(bytes: &[u8], signed: bool) -> bool {
+        match bytes {
+            [] => false,
+            [_] => true,
+            [first, second, ..] => match signed {
+                true => !((*first == 0x00 && *second < 0x80) || (*first == 0xFF && *second >= 0x80)),
+                false => *first != 0,
+            }
+        }
+    }
+
+    /// Determines a fewest bytes necessary to express non-negative value.
+    /// Zero takes 0 bytes, as in VarUInteger length prefix.
+    pub fn unsigned_byte_len(&self) -> Result<usize> {
+        self.check_neg()?;
+        Ok(common::bits_to_bytes(self.ubitsize()?))
+    }
+
+    /// Serializes value into Grams encoding: 4-bit length followed by
+    /// that many bytes of big-endian unsigned value. The 4-bit length allows at most
+    /// 15 bytes, so values wider than 120 bits raise range check error.
+    pub fn to_grams(&self) -> Result<Vec<bool>> {
+        self.check_neg()?;
+        if !self.ufits_in(120)? {
+            return err!(ExceptionCode::RangeCheckError, "{} cannot fit in 120 bits", self)
+        }
+        let bytes = match self.is_zero() {
+            true => Vec::new(),
+            false => self.take_value_of(|x| x.to_biguint())?.to_bytes_be(),
+        };
+        let mut bits = Vec::with_capacity(4 + bytes.len() * 8);
+        bits.extend((0..4).rev().map(|i| (bytes.len() >> i) & 1 != 0));
+        for byte in bytes {
+            bits.extend((0..8).rev().map(|i| (byte >> i) & 1 != 0));
+        }
+        Ok(bits)
+    }
+
+    /// Deserializes value from Grams encoding produced by to_grams().
+    pub fn from_grams(bits: &[bool]) -> Result<IntegerData> {
+        if bits.len() < 4 {
+            return err!(ExceptionCode::CellUnderflow, "no length in grams encoding")
+        }
+        let len = bits[..4].iter().fold(0usize, |acc, bit| (acc << 1) | *bit as usize);
+        if bits.len() != 4 + len * 8 {
+            return err!(ExceptionCode::CellUnderflow, "grams encoding should take {} bits", 4 + len * 8)
+        }
+        let value = bits[4..].iter().fold(num::BigUint::zero(), |acc, bit| {
+            (acc << 1) | num::BigUint::from(*bit as u8)
+        });
+        IntegerData::from(Int::from(value))
+    }
+
+    /// Interprets bytes both as signed and unsigned two's complement value
+    /// and returns (signed, unsigned) pair.
+    pub fn from_bytes_both(bytes: &[u8], endian: Endian) -> Result<(IntegerData, IntegerData)> {
+        let (unsigned, is_negative) = match endian {
+            Endian::Big => (
+                Int::from_bytes_be(num::bigint::Sign::Plus, bytes),
+                matches!(bytes.first(), Some(byte) if byte & 0x80 != 0)
+            ),
+            Endian::Little => (
+                Int::from_bytes_le(num::bigint::Sign::Plus, bytes),
+                matches!(bytes.last(), Some(byte) if byte & 0x80 != 0)
+            ),
+        };
+        let signed = match is_negative {
+            true => &unsigned - (Int::one() << (bytes.len() * 8)),
+            false => unsigned.clone(),
+        };
+        Ok((IntegerData::from(signed)?, IntegerData::from(unsigned)?))
+    }
+
+    /// Lazily yields bytes of big-endian two's complement representation of a given width.
+    /// NaN or value not fitting into the width yield None taking in account behavior of operation.
+    pub fn be_bytes_iter<T: OperationBehavior>(
+        &self,
+        width_bytes: usize,
+        signed: bool
+    ) -> Result<Option<impl Iterator<Item = u8>>> {
+        if self.is_nan() {
+            on_nan_parameter!(T)?;
+            return Ok(None)
+        }
+        let fits = match (width_bytes.checked_mul(8), signed) {
+            (Some(bits), true) => self.fits_in(bits)?,
+            (Some(bits), false) => self.ufits_in(bits)?,
+            // wider than any value, so only the sign matters
+            (None, signed) => signed || !self.is_neg(),
+        };
+        if !fits {
+            on_integer_overflow!(T)?;
+            return Ok(None)
+        }
+        // bytes of negative value are inverted bytes of (|value| - 1)
+        let (magnitude, fill) = match self.take_value_of(|x| Some(x.clone()))? {
+            value if value.is_negative() => ((-value - 1u32).magnitude().clone(), 0xFF),
+            value => (value.magnitude().clone(), 0),
+        };
+        Ok(Some((0..width_bytes).rev().map(move |index| {
+            let digit = magnitude.iter_u32_digits().nth(index / 4).unwrap_or(0);
+            (digit >> (8 * (index % 4))) as u8 ^ fill
+        })))
+    }
+
+    /// Writes big-endian two's complement representation of a given width into
+    /// a buffer at a given offset. Buffer stays unchanged on error.
+    pub fn write_be_into(
+        &self,
+        out: &mut [u8],
+        offset: usize,
+        width_bytes: usize,
+        signed: bool
+    ) -> Result<()> {
+        let target = match offset.checked_add(width_bytes) {
+            Some(end) if end <= out.len() => &mut out[offset..end],
+            _ => return err!(
+                ExceptionCode::RangeCheckError,
+                "{} bytes at offset {} do not fit in buffer of {} bytes", width_bytes, offset, out.len()
+            )
+        };
+        let bytes = self.be_bytes_iter::<Signaling>(width_bytes, signed)?
+            .ok_or_else(|| exception!(ExceptionCode::IntegerOverflow, "{} cannot fit in {} bytes", self, width_bytes))?;
+        for (byte, value) in target.iter_mut().zip(bytes) {
+            *byte = value;
+        }
+        Ok(())
+    }
+
+    /// Concatenates fixed-width big-endian two's complement serializations of values.
+    pub fn pack_array(values: &[IntegerData], bits: usize, signed: bool) -> Result<Vec<bool>> {
+        let total = match values.len().checked_mul(bits) {
+            Some(total) => total,
+            None => return err!(
+                ExceptionCode::RangeCheckError,
+                "{} values of {} bits do not fit in a bit string", values.len(), bits
+            )
+        };
+        let mut result = Vec::with_capacity(total);
+        for value in values {
+            let fits = match signed {
+                true => value.fits_in(bits)?,
+                false => value.ufits_in(bits)?,
+            };
+            if !fits {
+                return err!(ExceptionCode::RangeCheckError, "{} cannot fit in {}", value, bits)
+            }
+            for index in (0..bits).rev() {
+                result.push(value.bit_at(index)?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Splits bits produced by pack_array() back into a given count of values.
+    pub fn unpack_array(data: &[bool], count: usize, bits: usize, signed: bool) -> Result<Vec<IntegerData>> {
+        match count.checked_mul(bits) {
+            Some(total) if total <= data.len() => (),
+            _ => return err!(
+                ExceptionCode::CellUnderflow,
+                "{} bits cannot hold {} values of {}", data.len(), count, bits
+            )
+        }
+        if bits == 0 {
+            return Ok(vec![IntegerData::zero(); count])
+        }
+        let mut result = Vec::with_capacity(count);
+        for chunk in data.chunks(bits).take(count) {
+            let mut value = chunk.iter().fold(Int::zero(), |acc, bit| (acc << 1) + *bit as u32);
+            if signed && chunk.first() == Some(&true) {
+                value -= Int::one() << bits;
+            }
+            result.push(IntegerData::from(value)?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::integer::{behavior::Quiet, tests::exception_code};
+
+    #[test]
+    fn test_compact_round_trip() {
+        let large = IntegerData::from(Int::one() << 255).unwrap();
+        let values = [
+            IntegerData::from_i32(-64), IntegerData::from_i32(63), IntegerData::zero(),
+            IntegerData::from_i32(64), IntegerData::from_i32(-65), IntegerData::from_i32(1000), large
+        ];
+        for value in values {
+            let data = value.to_compact().unwrap();
+            assert_eq!(IntegerData::from_compact(&data).unwrap(), value);
+        }
+    }
 
+    #[test]
+    fn test_compact_encoding() {
+        assert_eq!(IntegerData::from_i32(-64).to_compact().unwrap(), vec![0x40]);
+        assert_eq!(IntegerData::from_i32(63).to_compact().unwrap(), vec![0x3F]);
+        assert_eq!(IntegerData::from_i32(64).to_compact().unwrap(), vec![0x81, 0x40]);
+        assert_eq!(IntegerData::from_i32(1000).to_compact().unwrap(), vec![0x82, 0x03, 0xE8]);
+        assert_eq!(exception_code(IntegerData::from_compact(&[0x82, 0x03])), Some(ExceptionCode::TypeCheckError));
+        assert_eq!(exception_code(IntegerData::from_compact(&[])), Some(ExceptionCode::TypeCheckError));
+    }
+
+    #[test]
+    fn test_is_canonical_encoding() {
+        assert!(!IntegerData::is_canonical_encoding(&[], false));
+        assert!(!IntegerData::is_canonical_encoding(&[], true));
+        assert!(IntegerData::is_canonical_encoding(&[0x05], false));
+        assert!(!IntegerData::is_canonical_encoding(&[0x00, 0x05], false));
+        assert!(IntegerData::is_canonical_encoding(&[0x01, 0x00], false));
+        assert!(IntegerData::is_canonical_encoding(&[0x00, 0x80], true));
+        assert!(!IntegerData::is_canonical_encoding(&[0x00, 0x7F], true));
+        assert!(!IntegerData::is_canonical_encoding(&[0xFF, 0x80], true));
+        assert!(IntegerData::is_canonical_encoding(&[0xFF, 0x7F], true));
+    }
+
+    #[test]
+    fn test_grams_round_trip() {
+        assert_eq!(IntegerData::zero().to_grams().unwrap(), vec![false; 4]);
+        assert_eq!(IntegerData::from_grams(&[false; 4]).unwrap(), IntegerData::zero());
+        let max = IntegerData::from((Int::one() << 120) - 1).unwrap();
+        let bits = max.to_grams().unwrap();
+        assert_eq!(bits.len(), 124);
+        assert_eq!(IntegerData::from_grams(&bits).unwrap(), max);
+        let bits = IntegerData::from_u32(0x1234).to_grams().unwrap();
+        assert_eq!(bits.len(), 20);
+        assert_eq!(IntegerData::from_grams(&bits).unwrap(), IntegerData::from_u32(0x1234));
+    }
+
+    #[test]
+    fn test_grams_errors() {
+        let too_big = IntegerData::from(Int::one() << 120).unwrap();
+        assert_eq!(exception_code(too_big.to_grams()), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::minus_one().to_grams()), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::from_grams(&[false; 3])), Some(ExceptionCode::CellUnderflow));
+        assert_eq!(exception_code(IntegerData::from_grams(&[false, false, false, true])), Some(ExceptionCode::CellUnderflow));
+    }
+
+    #[test]
+    fn test_from_bytes_both() {
+        let both = |bytes: &[u8], endian: Endian| IntegerData::from_bytes_both(bytes, endian).unwrap();
+        assert_eq!(both(&[0xFF], Endian::Big), (IntegerData::minus_one(), IntegerData::from_u32(255)));
+        assert_eq!(both(&[0x00, 0x80], Endian::Little), (IntegerData::from_i32(-32768), IntegerData::from_u32(32768)));
+        assert_eq!(both(&[0x00, 0x80], Endian::Big), (IntegerData::from_u32(128), IntegerData::from_u32(128)));
+        assert_eq!(both(&[], Endian::Big), (IntegerData::zero(), IntegerData::zero()));
+    }
+
+    #[test]
+    fn test_be_bytes_iter() {
+        let bytes = |value: i32, width: usize, signed: bool| -> Vec<u8> {
+            IntegerData::from_i32(value).be_bytes_iter::<Signaling>(width, signed).unwrap().unwrap().collect()
+        };
+        assert_eq!(bytes(0x1234, 4, false), vec![0x00, 0x00, 0x12, 0x34]);
+        assert_eq!(bytes(-2, 3, true), vec![0xFF, 0xFF, 0xFE]);
+        assert_eq!(bytes(-256, 2, true), vec![0xFF, 0x00]);
+        assert_eq!(bytes(0, 0, false), Vec::<u8>::new());
+        let wide: Vec<u8> = IntegerData::from(Int::one() << 200).unwrap()
+            .be_bytes_iter::<Signaling>(32, false).unwrap().unwrap().collect();
+        let mut expected = vec![0u8; 32];
+        expected[6] = 0x01;
+        assert_eq!(wide, expected);
+    }
+
+    #[test]
+    fn test_be_bytes_iter_errors() {
+        let code = |value: i32, width: usize, signed: bool| {
+            exception_code(IntegerData::from_i32(value).be_bytes_iter::<Signaling>(width, signed).map(|iter| iter.is_some()))
+        };
+        assert_eq!(code(256, 1, false), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(code(128, 1, true), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(code(-1, 4, false), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(code(-1, usize::MAX, false), Some(ExceptionCode::IntegerOverflow));
+        let nan = IntegerData::nan().be_bytes_iter::<Signaling>(4, false).map(|iter| iter.is_some());
+        assert_eq!(exception_code(nan), Some(ExceptionCode::IntegerOverflow));
+    }
+
+    #[test]
+    fn test_be_bytes_iter_behavior() {
+        assert!(IntegerData::from_u32(256).be_bytes_iter::<Quiet>(1, false).unwrap().is_none());
+        assert!(IntegerData::nan().be_bytes_iter::<Quiet>(1, false).unwrap().is_none());
+        let huge = IntegerData::minus_one().be_bytes_iter::<Signaling>(usize::MAX, true).unwrap().unwrap();
+        assert_eq!(huge.take(2).collect::<Vec<u8>>(), vec![0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_write_be_into() {
+        let mut buffer = [0xAA; 8];
+        IntegerData::from_u32(0x1234).write_be_into(&mut buffer, 2, 4, false).unwrap();
+        assert_eq!(buffer, [0xAA, 0xAA, 0x00, 0x00, 0x12, 0x34, 0xAA, 0xAA]);
+        IntegerData::from_i32(-2).write_be_into(&mut buffer, 6, 2, true).unwrap();
+        assert_eq!(buffer, [0xAA, 0xAA, 0x00, 0x00, 0x12, 0x34, 0xFF, 0xFE]);
+    }
+
+    #[test]
+    fn test_write_be_into_errors() {
+        let mut buffer = [0xAA; 8];
+        let value = IntegerData::from_u32(0x1234);
+        assert_eq!(exception_code(value.write_be_into(&mut buffer, 6, 4, false)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(value.write_be_into(&mut buffer, usize::MAX, 4, false)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(value.write_be_into(&mut buffer, 0, 1, false)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(exception_code(IntegerData::nan().write_be_into(&mut buffer, 0, 4, false)), Some(ExceptionCode::IntegerOverflow));
+        assert_eq!(buffer, [0xAA; 8]);
+    }
+
+    #[test]
+    fn test_unsigned_byte_len() {
+        assert_eq!(IntegerData::from_u32(255).unsigned_byte_len().unwrap(), 1);
+        assert_eq!(IntegerData::from_u32(256).unsigned_byte_len().unwrap(), 2);
+        assert_eq!(IntegerData::zero().unsigned_byte_len().unwrap(), 0);
+        assert_eq!(IntegerData::from((Int::one() << 256) - 1).unwrap().unsigned_byte_len().unwrap(), 32);
+        assert_eq!(exception_code(IntegerData::minus_one().unsigned_byte_len()), Some(ExceptionCode::RangeCheckError));
+    }
+
+    #[test]
+    fn test_pack_array_round_trip() {
+        let signed = [IntegerData::from_i32(1), IntegerData::from_i32(-1), IntegerData::from_i32(3)];
+        let data = IntegerData::pack_array(&signed, 3, true).unwrap();
+        assert_eq!(data, vec![false, false, true, true, true, true, false, true, true]);
+        assert_eq!(IntegerData::unpack_array(&data, 3, 3, true).unwrap(), signed.to_vec());
+        let unsigned = [IntegerData::from_i32(5), IntegerData::zero(), IntegerData::from_i32(7)];
+        let data = IntegerData::pack_array(&unsigned, 3, false).unwrap();
+        assert_eq!(IntegerData::unpack_array(&data, 3, 3, false).unwrap(), unsigned.to_vec());
+        assert_eq!(IntegerData::unpack_array(&data, 2, 3, false).unwrap(), unsigned[..2].to_vec());
+    }
+
+    #[test]
+    fn test_pack_array_errors() {
+        let values = [IntegerData::from_i32(4)];
+        assert_eq!(exception_code(IntegerData::pack_array(&values, 3, true)), Some(ExceptionCode::RangeCheckError));
+        assert_eq!(exception_code(IntegerData::pack_array(&values, 2, false)), Some(ExceptionCode::RangeCheckError));
+        let values = [IntegerData::zero(), IntegerData::zero()];
+        assert_eq!(exception_code(IntegerData::pack_array(&values, usize::MAX, false)), Some(ExceptionCode::RangeCheckError));
+        let data = [true; 5];
+        assert_eq!(exception_code(IntegerData::unpack_array(&data, 2, 3, false)), Some(ExceptionCode::CellUnderflow));
+        assert_eq!(exception_code(IntegerData::unpack_array(&[], usize::MAX, 2, false)), Some(ExceptionCode::CellUnderflow));
+    }
+
+        #[test]
+        fn test_grams_width_limit() {
+            let sixteen_bytes = IntegerData::from((Int::one() << 128) - 1).unwrap();
+            assert_eq!(exception_code(sixteen_bytes.to_grams()), Some(ExceptionCode::RangeCheckError));
+            let fifteen_bytes = IntegerData::from((Int::one() << 120) - 1).unwrap();
+            let bits = fifteen_bytes.to_grams().unwrap();
+            assert_eq!(bits.len(), 4 + 120);
+            assert_eq!(IntegerData::from_grams(&bits).unwrap(), fifteen_bytes);
+        }
+}
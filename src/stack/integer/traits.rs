@@ -0,0 +1,126 @@
+/*
+* Copyright (C) 2019-2021 TON Labs. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::{
+    behavior::Quiet,
+    Int,
+    IntegerData,
+    IntegerValue,
+};
+use std::{cmp::Ordering, ops::{Shl, Shr}};
+
+impl PartialEq<Int> for IntegerData {
+    fn eq(&self, other: &Int) -> bool {
+        match self.value {
+            IntegerValue::NaN(_) => false,
+            IntegerValue::Value(ref value) => value == other,
+        }
+    }
+}
+
+impl PartialOrd<Int> for IntegerData {
+    fn partial_cmp(&self, other: &Int) -> Option<Ordering> {
+        match self.value {
+            IntegerValue::NaN(_) => None,
+            IntegerValue::Value(ref value) => value.partial_cmp(other),
+        }
+    }
+}
+
+/// Quiet left shift: overflow yields NaN.
+impl Shl<usize> for &IntegerData {
+    type Output = IntegerData;
+
+    fn shl(self, shift: usize) -> IntegerData {
+        // any non-zero value shifted by more than 256 bits overflows
+        if shift > 256 {
+            return if self.is_zero() { self.clone() } else { IntegerData::nan() }
+        }
+        IntegerData::shl::<Quiet>(self, shift).unwrap_or_else(|_| IntegerData::nan())
+    }
+}
+
+/// Quiet left shift: overflow yields NaN.
+impl Shl<usize> for IntegerData {
+    type Output = IntegerData;
+
+    fn shl(self, shift: usize) -> IntegerData {
+        &self << shift
+    }
+}
+
+/// Arithmetic right shift: rounds towards negative infinity.
+impl Shr<usize> for &IntegerData {
+    type Output = IntegerData;
+
+    fn shr(self, shift: usize) -> IntegerData {
+        // shifting by 258 bits already leaves only the sign: 0 or -1
+        IntegerData::shr::<Quiet>(self, shift.min(258)).unwrap_or_else(|_| IntegerData::nan())
+    }
+}
+
+/// Arithmetic right shift: rounds towards negative infinity.
+impl Shr<usize> for IntegerData {
+    type Output = IntegerData;
+
+    fn shr(self, shift: usize) -> IntegerData {
+        &self >> shift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::One;
+
+    #[test]
+    fn test_compare_with_int() {
+        let value = IntegerData::from_i32(-5);
+        assert_eq!(value, Int::from(-5));
+        assert_ne!(value, Int::from(5));
+        assert!(value < Int::from(0));
+        assert!(value > Int::from(-6));
+        assert_eq!(value.partial_cmp(&Int::from(-5)), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_compare_nan_with_int() {
+        let nan = IntegerData::nan();
+        assert_ne!(nan, Int::from(0));
+        assert_eq!(nan.partial_cmp(&Int::from(0)), None);
+    }
+
+    #[test]
+    fn test_shift_operators() {
+        assert_eq!(&IntegerData::one() << 8, IntegerData::from_i32(256));
+        assert_eq!(IntegerData::from_i32(-5) >> 1, IntegerData::from_i32(-3));
+        assert_eq!(&IntegerData::from_i32(256) >> 8, IntegerData::one());
+        let max = IntegerData::from((Int::one() << 256) - 1).unwrap();
+        assert!((max << 1).is_nan());
+        assert!((IntegerData::nan() << 1).is_nan());
+        assert!((&IntegerData::nan() >> 1).is_nan());
+    }
+
+    #[test]
+    fn test_shift_operators_huge_shift() {
+        assert!((&IntegerData::one() << usize::MAX).is_nan());
+        assert!((&IntegerData::minus_one() << 257).is_nan());
+        assert!((&IntegerData::nan() << usize::MAX).is_nan());
+        assert_eq!(&IntegerData::zero() << usize::MAX, IntegerData::zero());
+        assert_eq!(IntegerData::from_i32(12345) >> usize::MAX, IntegerData::zero());
+        assert_eq!(IntegerData::from_i32(-12345) >> usize::MAX, IntegerData::minus_one());
+        let min = IntegerData::from(-(Int::one() << 256)).unwrap();
+        assert_eq!(&min >> 257, IntegerData::minus_one());
+        assert!((&IntegerData::nan() >> usize::MAX).is_nan());
+    }
+}